@@ -0,0 +1,79 @@
+use crate::error::Error;
+use crate::solution::{Answer, Answers};
+use std::collections::HashMap;
+use std::fs;
+
+pub const ANSWERS_FILE: &str = "answers.toml";
+
+pub type ExpectedAnswers = HashMap<usize, Answers>;
+
+// Reads the subset of TOML used by answers.toml:
+//
+//   [day01]
+//   part1 = 211899
+//   part2 = "some,string"
+pub fn read_answers(path: &str) -> Result<ExpectedAnswers, Error> {
+  let text = fs::read_to_string(path).map_err(|e| Error::Answers(format!("Could not read {}: {}", path, e)))?;
+  parse_answers(&text).map_err(|e| Error::Answers(format!("{}: {}", path, e)))
+}
+
+pub fn parse_answers(text: &str) -> Result<ExpectedAnswers, String> {
+  let mut answers: ExpectedAnswers = HashMap::new();
+  let mut current_day: Option<usize> = None;
+
+  for (i, line) in text.lines().enumerate() {
+    let line = line.split('#').next().unwrap().trim();
+    if line.is_empty() {
+      continue;
+    }
+
+    if line.starts_with('[') && line.ends_with(']') {
+      let day = line[1..line.len()-1].trim_start_matches("day").parse::<usize>()
+        .map_err(|_| format!("line {}: expected a [dayNN] section, found '{}'", i + 1, line))?;
+      answers.entry(day).or_insert((None, None));
+      current_day = Some(day);
+      continue;
+    }
+
+    let day = current_day.ok_or(format!("line {}: answer outside of a [dayNN] section", i + 1))?;
+    let mut parts = line.splitn(2, '=');
+    let key = parts.next().unwrap().trim();
+    let value = parts.next().ok_or(format!("line {}: expected 'key = value'", i + 1))?.trim();
+    let answer = parse_value(value).ok_or(format!("line {}: invalid value '{}'", i + 1, value))?;
+
+    let entry = answers.get_mut(&day).unwrap();
+    match key {
+      "part1" => entry.0 = Some(answer),
+      "part2" => entry.1 = Some(answer),
+      _ => return Err(format!("line {}: unknown key '{}', expected part1 or part2", i + 1, key)),
+    }
+  }
+
+  Ok(answers)
+}
+
+fn parse_value(value: &str) -> Option<Answer> {
+  if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+    Some(Answer::Str(value[1..value.len()-1].to_string()))
+  } else {
+    value.parse::<i64>().ok().map(Answer::Int)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parses_answers() {
+    let answers = parse_answers("# comment\n[day01]\npart1 = 42\n\n[day21]\npart2 = \"a,b\" # list\n").unwrap();
+    assert_eq!(answers.get(&1), Some(&(Some(Answer::Int(42)), None)));
+    assert_eq!(answers.get(&21), Some(&(None, Some(Answer::Str(String::from("a,b"))))));
+  }
+
+  #[test]
+  fn rejects_unknown_keys() {
+    assert!(parse_answers("[day01]\npart3 = 1\n").is_err());
+    assert!(parse_answers("part1 = 1\n").is_err());
+  }
+}
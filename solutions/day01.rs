@@ -1,51 +1,86 @@
-use crate::helpers;
-use std::error::Error;
+use crate::helpers::{self, InputSource};
+use crate::solution::{Answer, Solution};
+use crate::error::{Error, ParseError, SourceLine};
 
-const INPUT_FILE: &str = "input/day01";
+pub const INPUT_FILE: &str = "input/day01";
 
-pub fn solve() -> Result<(), Box<dyn Error>> {
-  let numbers = parse_input()?;
-  
-  solve_part_one(&numbers);
-  solve_part_two(&numbers);
+const DAY: u8 = 1;
 
+pub struct Day01;
 
+impl Solution for Day01 {
+  type Input = Vec<u32>;
 
-  return Ok(());
+  fn parse(input: &InputSource) -> Result<Self::Input, Error> {
+    parse_input(input)
+  }
+
+  fn part_one(numbers: &Self::Input) -> Result<Answer, Error> {
+    Ok(solve_part_one(numbers))
+  }
+
+  fn part_two(numbers: &Self::Input) -> Result<Answer, Error> {
+    Ok(solve_part_two(numbers))
+  }
 }
 
-fn solve_part_one(numbers: &Vec<u32>) {
+fn solve_part_one(numbers: &[u32]) -> Answer {
   for i in 0..(numbers.len()-1) {
     for j in (i+1)..numbers.len() {
       if numbers[i] + numbers[j] == 2020 {
-        println!("Found numbers with sum 2020: {} and {}. Product is {}", numbers[i], numbers[j], numbers[i] * numbers[j]);
+        return (numbers[i] * numbers[j]).into();
       }
     }
   }
+  Answer::None
 }
-fn solve_part_two(numbers: &Vec<u32>) {
-  for i in 0..(numbers.len()-2) {
+fn solve_part_two(numbers: &[u32]) -> Answer {
+  for i in 0..numbers.len().saturating_sub(2) {
     for j in (i+1)..numbers.len()-1 {
       for k in j+1..numbers.len() {
         if numbers[i] + numbers[j] + numbers[k] == 2020 {
-          println!("Found numbers with sum 2020: {}, {} and {}. Product is {}", numbers[i], numbers[j], numbers[k], numbers[i] * numbers[j] * numbers[k]);
+          return (numbers[i] * numbers[j] * numbers[k]).into();
         }
       }
     }
   }
+  Answer::None
 } 
 
-fn parse_input() -> Result<Vec<u32>, Box<dyn Error>> {
+fn parse_input(input: &InputSource) -> Result<Vec<u32>, Error> {
 
   let mut xs: Vec<u32> = vec![];
 
-  println!("{}", INPUT_FILE);
+  let lines = helpers::read_lines(input)?;
 
-  let lines = helpers::read_lines(INPUT_FILE)?;
+  for (i, line) in lines.enumerate() {
+    let line = line?;
+    xs.push(SourceLine::new(DAY, i + 1, &line).parse::<u32>(&line)?);
+  }
+  if xs.is_empty() {
+    return Err(ParseError::missing_line(DAY, 1, "expected a number").into());
+  }
 
-  for line in lines {
-    xs.push(line?.trim().parse::<u32>()?);
+  Ok(xs)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn solves_example() {
+    let input = Day01::parse(&InputSource::File("input/test/day01".into())).unwrap();
+    assert_eq!(Day01::part_one(&input).unwrap(), Answer::Int(514579));
+    assert_eq!(Day01::part_two(&input).unwrap(), Answer::Int(241861950));
   }
 
-  return Ok(xs);
-}
\ No newline at end of file
+  #[test]
+  fn rejects_empty_input() {
+    match Day01::parse(&InputSource::Text(String::new())) {
+      Err(Error::Parse(e)) => assert_eq!((e.line, e.message.as_str()), (1, "expected a number")),
+      _ => panic!("expected a parse error"),
+    }
+    assert_eq!(Day01::part_two(&vec![2020]).unwrap(), Answer::None);
+  }
+}
@@ -1,42 +1,54 @@
-use crate::helpers;
-use std::error::Error;
+use crate::helpers::{self, InputSource};
+use crate::solution::{Answer, Solution};
+use crate::error::{Error, SourceLine};
 
-const INPUT_FILE: &str = "input/day02";
+pub const INPUT_FILE: &str = "input/day02";
 
-struct InputLine {
+const DAY: u8 = 2;
+
+pub struct InputLine {
   min: u32,
   max: u32,
   character: char,
   password: String,
 }
 
-pub fn solve() -> Result<(), Box<dyn Error>> {
-  let inputs = parse_input()?;
-  
-  solve_part_one(&inputs);
-  solve_part_two(&inputs);
+pub struct Day02;
+
+impl Solution for Day02 {
+  type Input = Vec<InputLine>;
+
+  fn parse(input: &InputSource) -> Result<Self::Input, Error> {
+    parse_input(input)
+  }
 
-  return Ok(());
+  fn part_one(inputs: &Self::Input) -> Result<Answer, Error> {
+    Ok(solve_part_one(inputs))
+  }
+
+  fn part_two(inputs: &Self::Input) -> Result<Answer, Error> {
+    Ok(solve_part_two(inputs))
+  }
 }
 
-fn solve_part_one(inputs: &Vec<InputLine>) {
+fn solve_part_one(inputs: &Vec<InputLine>) -> Answer {
   let mut count = 0;
   for input in inputs {
-    if check_password(&input) {
+    if check_password(input) {
       count += 1;
     }
   }
-  println!("Valid passwords: {}", count);
+  count.into()
 }
 
-fn solve_part_two(inputs: &Vec<InputLine>) {
+fn solve_part_two(inputs: &Vec<InputLine>) -> Answer {
   let mut count = 0;
   for input in inputs {
-    if check_password_part_two(&input) {
+    if check_password_part_two(input) {
       count += 1;
     }
   }
-  println!("Valid passwords: {}", count);
+  count.into()
 }
 
 fn check_password(input: &InputLine) -> bool {
@@ -48,7 +60,7 @@ fn check_password(input: &InputLine) -> bool {
     }
   }
 
-  return count >= input.min && count <= input.max;
+  count >= input.min && count <= input.max
 }
 
 fn check_password_part_two(input: &InputLine) -> bool {
@@ -57,24 +69,29 @@ fn check_password_part_two(input: &InputLine) -> bool {
   let first_matches = chars[(input.min - 1) as usize] == input.character;
   let second_matches = chars[(input.max - 1) as usize] == input.character;
 
-  return first_matches != second_matches;
+  first_matches != second_matches
 }
 
-fn parse_input() -> Result<Vec<InputLine>, Box<dyn Error>> {
+fn parse_input(input: &InputSource) -> Result<Vec<InputLine>, Error> {
 
   let mut inputs: Vec<InputLine> = vec![];
 
-  let lines = helpers::read_lines(INPUT_FILE)?;
+  let lines = helpers::read_lines(input)?;
 
-  for line in lines {
+  for (i, line) in lines.enumerate() {
     let line = line?;
-    let parts = line.split(' ').collect::<Vec<_>>();
-    let limits = parts[0].split('-').collect::<Vec<_>>();
-
-    let min = limits[0].parse::<u32>()?;
-    let max = limits[1].parse::<u32>()?;
-    let character = parts[1].chars().next().unwrap();
-    let password = parts[2];
+    let source = SourceLine::new(DAY, i + 1, &line);
+    let mut parts = line.split(' ');
+    let mut limits = source.expect(parts.next(), "expected a policy")?.split('-');
+
+    let min = source.parse::<u32>(source.expect(limits.next(), "expected a minimum")?)?;
+    let max = source.parse::<u32>(source.expect(limits.next(), "expected a maximum")?)?;
+    let character_part = source.expect(parts.next(), "expected a character")?;
+    let character = match character_part.chars().next() {
+      Some(c) => c,
+      None => return Err(source.error(character_part, "expected a character").into()),
+    };
+    let password = source.expect(parts.next(), "expected a password")?;
     inputs.push(InputLine {
       min,
       max,
@@ -83,5 +100,17 @@ fn parse_input() -> Result<Vec<InputLine>, Box<dyn Error>> {
     });
   }
 
-  return Ok(inputs);
-}
\ No newline at end of file
+  Ok(inputs)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn solves_example() {
+    let input = Day02::parse(&InputSource::File("input/test/day02".into())).unwrap();
+    assert_eq!(Day02::part_one(&input).unwrap(), Answer::Int(2));
+    assert_eq!(Day02::part_two(&input).unwrap(), Answer::Int(1));
+  }
+}
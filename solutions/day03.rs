@@ -1,59 +1,66 @@
-use crate::helpers;
-use std::error::Error;
+use crate::helpers::{self, Grid, InputSource};
+use crate::solution::{Answer, Solution};
+use crate::error::{Error, ParseError, SourceLine};
 
-const INPUT_FILE: &str = "input/day03";
+pub const INPUT_FILE: &str = "input/day03";
 
-struct Map {
-  height: usize,
-  width: usize,
-  trees: Vec<Vec<bool>>,
-}
+const DAY: u8 = 3;
+
+pub type Map = Grid<bool>;
 
 type Point = (usize, usize);
 type Route = (usize, usize);
 
-pub fn solve() -> Result<(), Box<dyn Error>> {
-  let map = parse_input()?;
+pub struct Day03;
+
+impl Solution for Day03 {
+  type Input = Map;
+
+  fn parse(input: &InputSource) -> Result<Self::Input, Error> {
+    parse_input(input)
+  }
 
-  solve_part_one(&map);
-  solve_part_two(&map);
+  fn part_one(map: &Self::Input) -> Result<Answer, Error> {
+    Ok(solve_part_one(map))
+  }
 
-  return Ok(());
+  fn part_two(map: &Self::Input) -> Result<Answer, Error> {
+    Ok(solve_part_two(map))
+  }
 }
 
-fn solve_part_one(map: &Map) {
-  let answer = number_of_trees_on_route(map, (3, 1));
-  println!("Part 1 answer: {}", answer);
+fn solve_part_one(map: &Map) -> Answer {
+  number_of_trees_on_route(map, (3, 1)).into()
 }
 
-fn solve_part_two(map: &Map) {
+fn solve_part_two(map: &Map) -> Answer {
   let slopes: Vec<Route> = vec![(1,1), (3,1), (5,1), (7,1), (1,2)];
 
   let mut product: u64 = 1;
   for slope in slopes {
     product *= number_of_trees_on_route(map, slope) as u64;
   }
-  println!("Part 2 answer: {}", product);
+  product.into()
 }
 
 fn check_point_for_tree(map: &Map, point: Point) -> bool {
   let (x, y) = point;
-  if y < 0 || y >= map.height {
-    return false;
+  if y >= map.height() {
+    false
   } else {
-    return map.trees[y][x % map.width];
+    *map.get_wrapping(x as i64, y as i64)
   }
 }
 
 fn get_points_on_route(map: &Map, route: Route) -> Vec<Point> {
   let (x,y) = route;
-  let num_steps = map.height / y + 1;
+  let num_steps = map.height() / y + 1;
 
   let mut points: Vec<Point> = vec![];
   for i in 0..num_steps {
     points.push((i * x, i * y));
   }
-  return points;
+  points
 }
 
 fn number_of_trees_on_route(map: &Map, route: Route) -> u32 {
@@ -65,24 +72,36 @@ fn number_of_trees_on_route(map: &Map, route: Route) -> u32 {
     }
   }
 
-  return count;
+  count
 }
 
-fn parse_input() -> Result<Map, Box<dyn Error>> {
-  let lines = helpers::read_lines(INPUT_FILE)?;
+fn parse_input(input: &InputSource) -> Result<Map, Error> {
+  let lines = helpers::read_lines(input)?.collect::<Result<Vec<_>, _>>()?;
+  let map = Grid::parse(SourceLine::numbered(DAY, &lines))?;
 
-  let trees = lines.map(|line| {
-    match line {
-      Ok(l) => l.chars().map(|c| c == '#').collect::<Vec<_>>(),
-      Err(_) => vec![]
-    }
-  }).collect::<Vec<Vec<bool>>>();
+  if map.width() == 0 || map.height() == 0 {
+    return Err(ParseError::missing_line(DAY, 1, "expected a map").into());
+  }
+
+  Ok(map)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
 
-  let map = Map {
-    width: trees[0].len(),
-    height: trees.len(),
-    trees,
-  };
+  #[test]
+  fn solves_example() {
+    let input = Day03::parse(&InputSource::File("input/test/day03".into())).unwrap();
+    assert_eq!(Day03::part_one(&input).unwrap(), Answer::Int(7));
+    assert_eq!(Day03::part_two(&input).unwrap(), Answer::Int(336));
+  }
 
-  return Ok(map);
-}
\ No newline at end of file
+  #[test]
+  fn rejects_a_map_without_columns() {
+    match Day03::parse(&InputSource::Text("\n\n".into())) {
+      Err(Error::Parse(e)) => assert_eq!((e.line, e.message.as_str()), (1, "expected a map")),
+      _ => panic!("expected a parse error"),
+    }
+  }
+}
@@ -1,41 +1,52 @@
-use crate::helpers;
-use std::error::Error;
+use crate::helpers::{self, InputSource};
+use crate::solution::{Answer, Solution};
+use crate::error::Error;
 use std::collections::HashMap;
 
-const INPUT_FILE: &str = "input/day04";
+pub const INPUT_FILE: &str = "input/day04";
 
 type Passport = HashMap<String, String>;
 
-pub fn solve() -> Result<(), Box<dyn Error>> {
-  let passports = parse_input()?;
+pub struct Day04;
 
-  solve_part_one(&passports);
-  solve_part_two(&passports);
-  return Ok(());
+impl Solution for Day04 {
+  type Input = Vec<Passport>;
+
+  fn parse(input: &InputSource) -> Result<Self::Input, Error> {
+    parse_input(input)
+  }
+
+  fn part_one(passports: &Self::Input) -> Result<Answer, Error> {
+    Ok(solve_part_one(passports))
+  }
+
+  fn part_two(passports: &Self::Input) -> Result<Answer, Error> {
+    Ok(solve_part_two(passports))
+  }
 }
 
-fn solve_part_one(passports: &Vec<Passport>) {
+fn solve_part_one(passports: &Vec<Passport>) -> Answer {
   let mut count = 0;
   for passport in passports {
     if passport_is_valid(passport) {
       count += 1;
     }
   }
-  println!("Part 1 answer: {}", count);
+  count.into()
 }
 
-fn solve_part_two(passports: &Vec<Passport>) {
+fn solve_part_two(passports: &Vec<Passport>) -> Answer {
   let mut count = 0;
   for passport in passports {
     if passport_is_valid_part_two(passport) {
       count += 1;
     }
   }
-  println!("Part 2 answer: {}", count);
+  count.into()
 }
 
 fn passport_is_valid(passport: &Passport) -> bool {
-  return passport.get(&String::from("byr")).is_some()
+  passport.get(&String::from("byr")).is_some()
     && passport.get(&String::from("iyr")).is_some()
     && passport.get(&String::from("eyr")).is_some()
     && passport.get(&String::from("hgt")).is_some()
@@ -45,7 +56,7 @@ fn passport_is_valid(passport: &Passport) -> bool {
 }
 
 fn passport_is_valid_part_two(passport: &Passport) -> bool {
-  return byr_valid(passport)
+  byr_valid(passport)
     && iyr_valid(passport)
     && eyr_valid(passport)
     && hgt_valid(passport)
@@ -56,13 +67,13 @@ fn passport_is_valid_part_two(passport: &Passport) -> bool {
 
 fn byr_valid(passport: &Passport) -> bool {
   let val = passport.get("byr");
-  return match val {
+  match val {
     None => false,
     Some(b) => {
       let val = b.parse::<u32>();
       match val {
         Err(_) => false,
-        Ok(v) => v >= 1920 && v <= 2002,
+        Ok(v) => (1920..=2002).contains(&v),
       }
     }
   }
@@ -70,13 +81,13 @@ fn byr_valid(passport: &Passport) -> bool {
 
 fn iyr_valid(passport: &Passport) -> bool {
   let val = passport.get("iyr");
-  return match val {
+  match val {
     None => false,
     Some(b) => {
       let val = b.parse::<u32>();
       match val {
         Err(_) => false,
-        Ok(v) => v >= 2010 && v <= 2020,
+        Ok(v) => (2010..=2020).contains(&v),
       }
     }
   }
@@ -84,13 +95,13 @@ fn iyr_valid(passport: &Passport) -> bool {
 
 fn eyr_valid(passport: &Passport) -> bool {
   let val = passport.get("eyr");
-  return match val {
+  match val {
     None => false,
     Some(b) => {
       let val = b.parse::<u32>();
       match val {
         Err(_) => false,
-        Ok(v) => v >= 2020 && v <= 2030,
+        Ok(v) => (2020..=2030).contains(&v),
       }
     }
   }
@@ -98,7 +109,7 @@ fn eyr_valid(passport: &Passport) -> bool {
 
 fn hgt_valid(passport: &Passport) -> bool {
   let val = passport.get("hgt");
-  return match val {
+  match val {
     None => false,
     Some(b) => {
       let (hgt, unit) = b.split_at(b.len() - 2);
@@ -106,8 +117,8 @@ fn hgt_valid(passport: &Passport) -> bool {
       match val {
         Err(_) => false,
         Ok(v) => match unit {
-          "in" => v >= 59 && v <= 76,
-          "cm" => v >= 150 && v <= 193,
+          "in" => (59..=76).contains(&v),
+          "cm" => (150..=193).contains(&v),
           _ => false,
         }
       }
@@ -117,7 +128,7 @@ fn hgt_valid(passport: &Passport) -> bool {
 
 fn hcl_valid(passport: &Passport) -> bool {
   let val = passport.get("hcl");
-  return match val {
+  match val {
     None => false,
     Some(b) => {
       let (start, val) = b.split_at(1);
@@ -128,38 +139,28 @@ fn hcl_valid(passport: &Passport) -> bool {
 
 fn ecl_valid(passport: &Passport) -> bool {
   let val = passport.get("ecl");
-  return match val {
+  match val {
     None => false,
-    Some(b) => 
-      match b.as_str() {
-        "amb" => true,
-        "blu" => true,
-        "brn" => true,
-        "gry" => true,
-        "grn" => true,
-        "hzl" => true,
-        "oth" => true,
-        _ => false,
-      }
+    Some(b) => matches!(b.as_str(), "amb" | "blu" | "brn" | "gry" | "grn" | "hzl" | "oth")
   }
 }
 
 fn pid_valid(passport: &Passport) -> bool {
   let val = passport.get("pid");
-  return match val {
+  match val {
     None => false,
-    Some(b) => b.len() == 9 && b.chars().all(|c| c.is_digit(10))
+    Some(b) => b.len() == 9 && b.chars().all(|c| c.is_ascii_digit())
   }
 }
 
-fn parse_input() -> Result<Vec<Passport>, Box<dyn Error>> {
-  let lines = helpers::read_lines(INPUT_FILE)?;
+fn parse_input(input: &InputSource) -> Result<Vec<Passport>, Error> {
+  let lines = helpers::read_lines(input)?;
 
   let mut passports: Vec<Passport> = vec![]; 
   let mut current_entry: Passport = HashMap::new();
   for line in lines {
     let line = line?;
-    if line.trim().len() == 0 {
+    if line.trim().is_empty() {
       // new passport, save current and create new
       passports.push(current_entry);
       current_entry = HashMap::new();
@@ -179,5 +180,22 @@ fn parse_input() -> Result<Vec<Passport>, Box<dyn Error>> {
     passports.push(current_entry);
   }
 
-  return Ok(passports);
-}
\ No newline at end of file
+  Ok(passports)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn solves_example() {
+    let input = Day04::parse(&InputSource::File("input/test/day04".into())).unwrap();
+    assert_eq!(Day04::part_one(&input).unwrap(), Answer::Int(2));
+  }
+
+  #[test]
+  fn solves_example_2() {
+    let input = Day04::parse(&InputSource::File("input/test/day04_2".into())).unwrap();
+    assert_eq!(Day04::part_two(&input).unwrap(), Answer::Int(4));
+  }
+}
@@ -1,27 +1,35 @@
-use crate::helpers;
-use std::error::Error;
+use crate::helpers::{self, InputSource};
+use crate::solution::{Answer, Solution};
+use crate::error::Error;
 
-const INPUT_FILE: &str = "input/day05";
+pub const INPUT_FILE: &str = "input/day05";
 
 type Seat = (u32, u32);
 
-pub fn solve() -> Result<(), Box<dyn Error>> {
-  let seats = parse_input()?;
-  let seat_numbers = seats.iter().map(|s| seat_number_from_bsp(s)).collect::<Vec<_>>();
+pub struct Day05;
 
-  solve_part_one(&seat_numbers);
-  solve_part_two(&seat_numbers);
-  return Ok(());
-}
+impl Solution for Day05 {
+  type Input = Vec<Seat>;
+
+  fn parse(input: &InputSource) -> Result<Self::Input, Error> {
+    parse_input(input)
+  }
 
-fn solve_part_one(seats: &Vec<Seat>) {
-  let max_id = seats.iter().map(|s| get_seat_id(s)).max().unwrap(); 
+  fn part_one(seats: &Self::Input) -> Result<Answer, Error> {
+    Ok(solve_part_one(seats))
+  }
+
+  fn part_two(seats: &Self::Input) -> Result<Answer, Error> {
+    Ok(solve_part_two(seats))
+  }
+}
 
-  println!("Part 1 answer: {}", max_id);
+fn solve_part_one(seats: &[Seat]) -> Answer {
+  seats.iter().map(get_seat_id).max().into()
 }
 
-fn solve_part_two(seats: &Vec<Seat>) {
-  let mut seat_ids = seats.iter().map(|s| get_seat_id(s)).collect::<Vec<_>>();
+fn solve_part_two(seats: &[Seat]) -> Answer {
+  let mut seat_ids = seats.iter().map(get_seat_id).collect::<Vec<_>>();
   seat_ids.sort();
 
   let mut prev_id: Option<u32> = None;
@@ -29,15 +37,16 @@ fn solve_part_two(seats: &Vec<Seat>) {
   for seat_id in seat_ids {
     if let Some(id) = prev_id {
       if id + 1 != seat_id {
-        println!("Part 2 answer: {}", id + 1);
-        break;
+        return (id + 1).into();
       }
     } 
     prev_id = Some(seat_id);
   }
+
+  Answer::None
 }
 
-fn seat_number_from_bsp(bsp: &String) -> Seat {
+fn seat_number_from_bsp(bsp: &str) -> Seat {
   bsp.chars().fold((0,0), |(row, col), c| {
     match c {
       'F' => (2 * row, col),
@@ -53,7 +62,19 @@ fn get_seat_id((row, col): &Seat) -> u32 {
   8 * row + col
 }
 
-fn parse_input() -> Result<Vec<String>, Box<dyn Error>> {
-  let lines = helpers::read_lines(INPUT_FILE)?.map(|l| l.unwrap()).collect::<Vec<_>>();
-  return Ok(lines);
-}
\ No newline at end of file
+fn parse_input(input: &InputSource) -> Result<Vec<Seat>, Error> {
+  let lines = helpers::read_lines(input)?.collect::<Result<Vec<_>, _>>()?;
+  Ok(lines.iter().map(|line| seat_number_from_bsp(line)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn solves_example() {
+    let input = Day05::parse(&InputSource::File("input/test/day05".into())).unwrap();
+    assert_eq!(Day05::part_one(&input).unwrap(), Answer::Int(820));
+    assert_eq!(Day05::part_two(&input).unwrap(), Answer::Int(120));
+  }
+}
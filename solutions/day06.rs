@@ -1,30 +1,40 @@
-use crate::helpers;
-use std::error::Error;
+use crate::helpers::{self, InputSource};
+use crate::solution::{Answer, Solution};
+use crate::error::Error;
 use std::collections::HashSet;
 
-const INPUT_FILE: &str = "input/day06";
+pub const INPUT_FILE: &str = "input/day06";
 
 type Group = Vec<String>;
 
-pub fn solve() -> Result<(), Box<dyn Error>> {
-  let groups = parse_input()?;
+pub struct Day06;
 
-  solve_part_one(&groups);
-  solve_part_two(&groups);
+impl Solution for Day06 {
+  type Input = Vec<Group>;
 
-  return Ok(());
+  fn parse(input: &InputSource) -> Result<Self::Input, Error> {
+    parse_input(input)
+  }
+
+  fn part_one(groups: &Self::Input) -> Result<Answer, Error> {
+    Ok(solve_part_one(groups))
+  }
+
+  fn part_two(groups: &Self::Input) -> Result<Answer, Error> {
+    Ok(solve_part_two(groups))
+  }
 }
 
-fn solve_part_one(groups: &Vec<Group>) {
+fn solve_part_one(groups: &[Group]) -> Answer {
 
   let result: u32 = groups.iter().map(|g| any_in_group_answers_yes(g).len() as u32).sum();
-  println!("Part 1 answer: {}", result);
+  result.into()
 }
 
-fn solve_part_two(groups: &Vec<Group>) {
+fn solve_part_two(groups: &[Group]) -> Answer {
 
   let result: u32 = groups.iter().map(|g| all_in_group_answer_yes(g).len() as u32).sum();
-  println!("Part 2 answer: {}", result);
+  result.into()
 }
 
 fn any_in_group_answers_yes (group: &Group) -> Vec<char> {
@@ -47,21 +57,18 @@ fn all_in_group_answer_yes (group: &Group) -> Vec<char> {
     }
   }
 
-  match all_answers {
-    None => vec![],
-    Some(a) => a
-  }
+  all_answers.unwrap_or_default()
 }
 
-fn parse_input() -> Result<Vec<Group>, Box<dyn Error>> {
-  let lines = helpers::read_lines(INPUT_FILE)?;
+fn parse_input(input: &InputSource) -> Result<Vec<Group>, Error> {
+  let lines = helpers::read_lines(input)?;
 
   let mut groups: Vec<Group> = vec![]; 
   let mut current_entry: Group = vec![];
 
   for line in lines {
     let line = line?;
-    if line.trim().len() == 0 {
+    if line.trim().is_empty() {
       groups.push(current_entry);
       current_entry = vec![];
     } else {
@@ -73,5 +80,17 @@ fn parse_input() -> Result<Vec<Group>, Box<dyn Error>> {
     groups.push(current_entry);
   }
 
-  return Ok(groups);
-}
\ No newline at end of file
+  Ok(groups)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn solves_example() {
+    let input = Day06::parse(&InputSource::File("input/test/day06".into())).unwrap();
+    assert_eq!(Day06::part_one(&input).unwrap(), Answer::Int(11));
+    assert_eq!(Day06::part_two(&input).unwrap(), Answer::Int(6));
+  }
+}
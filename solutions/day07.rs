@@ -1,24 +1,39 @@
-use crate::helpers;
-use std::error::Error;
+use crate::helpers::{self, InputSource};
+use crate::solution::{Answer, Solution};
+use crate::error::{Error, ParseError, SourceLine};
 use std::collections::HashMap;
 
-const INPUT_FILE: &str = "input/day07";
+pub const INPUT_FILE: &str = "input/day07";
+
+const DAY: u8 = 7;
 
 type BagRules = HashMap<String, Vec<(String, u32)>>;
 
-pub fn solve() -> Result<(), Box<dyn Error>> {
-  let rules = parse_input()?;
-  let reversed_rules = get_reversed_rules(&rules);
+pub struct Day07;
+
+impl Solution for Day07 {
+  type Input = BagRules;
+
+  fn parse(input: &InputSource) -> Result<Self::Input, Error> {
+    parse_input(input)
+  }
 
-  solve_part_one(&rules, &reversed_rules);
-  solve_part_two(&rules);
+  fn part_one(rules: &Self::Input) -> Result<Answer, Error> {
+    Ok(solve_part_one(rules))
+  }
 
-  return Ok(());
+  fn part_two(rules: &Self::Input) -> Result<Answer, Error> {
+    Ok(solve_part_two(rules))
+  }
 }
 
-fn solve_part_one(_rules: &BagRules, reversed_rules: &BagRules) {
+fn solve_part_one(rules: &BagRules) -> Answer {
+  let reversed_rules = get_reversed_rules(rules);
 
-  let base_rule = reversed_rules.get("shiny gold").unwrap();
+  let base_rule = match reversed_rules.get("shiny gold") {
+    None => return 0.into(),
+    Some(r) => r,
+  };
   let mut valid_bags: Vec<String> = base_rule.iter().map(|(color, _count)| color.clone()).collect();
   let mut index = 0;
   loop {
@@ -38,19 +53,19 @@ fn solve_part_one(_rules: &BagRules, reversed_rules: &BagRules) {
 
     index = valid_bags.len();
 
-    if new_bags.len() == 0 {
+    if new_bags.is_empty() {
       break;
     } else {
       valid_bags.append(&mut new_bags);
     }
   }
 
-  println!("Part 1 answer: {:?}", valid_bags.len());
+  valid_bags.len().into()
 }
 
-fn solve_part_two(rules: &BagRules) {
+fn solve_part_two(rules: &BagRules) -> Answer {
   let result = get_number_of_bags_for_color(rules, &String::from("shiny gold"));
-  println!("Part 2 answer: {}", result - 1);
+  (result - 1).into()
 }
 
 fn get_number_of_bags_for_color(rules: &BagRules, color: &String) -> u32 {
@@ -89,45 +104,75 @@ fn get_reversed_rules (rules: &BagRules) -> BagRules {
     }
   }
 
-  return reversed_rules;
+  reversed_rules
 }
 
-fn parse_input() -> Result<BagRules, Box<dyn Error>> {
-  let lines = helpers::read_lines(INPUT_FILE)?;
+fn parse_input(input: &InputSource) -> Result<BagRules, Error> {
+  let lines = helpers::read_lines(input)?;
 
   let mut bag_rules: BagRules = HashMap::new();
 
-  for line in lines {
+  for (i, line) in lines.enumerate() {
     let line = line?;
+    let source = SourceLine::new(DAY, i + 1, &line);
     let mut parts = line.split(" contain ");
-    let container_part = parts.next().unwrap().to_string();
-    let container_color = parse_color_of_bag(container_part);
+    let container_part = source.expect(parts.next(), "expected a bag")?;
+    let container_color = parse_color_of_bag(&source, container_part)?;
 
     let mut containees: Vec<(String, u32)> = vec![];
 
-    let contained_part = parts.next().unwrap().to_string();
+    let contained_part = source.expect(parts.next(), "expected ' contain '")?;
     let contained_parts = contained_part.split(", ");
 
     for p in contained_parts {
-      let mut parts = p.splitn(2, " ");
-      let count = parts.next().unwrap().parse::<u32>();
+      let mut parts = p.splitn(2, ' ');
+      let count = source.expect(parts.next(), "expected a count")?.parse::<u32>();
       if let Ok(num) = count {
-        let color = parse_color_of_bag(parts.next().unwrap().to_string());
+        let color = parse_color_of_bag(&source, source.expect(parts.next(), "expected a bag")?)?;
         containees.push((color, num));
       }
     }
 
-    if bag_rules.get(&container_color).is_some() {
-      println!("Color already existed. Handle this? ({})", container_color);
+    if bag_rules.contains_key(&container_color) {
+      return Err(source.error(container_part, &format!("there is already a rule for {} bags", container_color)).into());
     }
 
     bag_rules.insert(container_color, containees);
   }
 
-  return Ok(bag_rules);
+  Ok(bag_rules)
+}
+
+fn parse_color_of_bag(source: &SourceLine, bag_string: &str) -> Result<String, ParseError> {
+  match bag_string.rfind(" bag") {
+    Some(index) => Ok(bag_string[..index].to_string()),
+    None => Err(source.error(bag_string, "expected a bag")),
+  }
 }
 
-fn parse_color_of_bag(bag_string: String) -> String {
-  let index = bag_string.rfind("bag").unwrap();
-  bag_string[..index-1].to_string()
-}
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn solves_example() {
+    let input = Day07::parse(&InputSource::File("input/test/day07".into())).unwrap();
+    assert_eq!(Day07::part_one(&input).unwrap(), Answer::Int(4));
+    assert_eq!(Day07::part_two(&input).unwrap(), Answer::Int(32));
+  }
+
+  #[test]
+  fn solves_example_2() {
+    let input = Day07::parse(&InputSource::File("input/test/day07_2".into())).unwrap();
+    assert_eq!(Day07::part_one(&input).unwrap(), Answer::Int(0));
+    assert_eq!(Day07::part_two(&input).unwrap(), Answer::Int(126));
+  }
+  #[test]
+  fn rejects_a_second_rule_for_a_bag() {
+    let input = "light red bags contain 1 bright white bag.\nlight red bags contain no other bags.\n";
+    match Day07::parse(&InputSource::Text(input.into())) {
+      Err(Error::Parse(e)) => assert_eq!((e.line, e.message.as_str()), (2, "there is already a rule for light red bags")),
+      _ => panic!("expected a parse error"),
+    }
+  }
+}
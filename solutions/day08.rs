@@ -1,108 +1,229 @@
-use crate::helpers;
-use std::error::Error;
-use std::collections::HashMap;
+use crate::helpers::{self, InputSource};
+use crate::solution::{Answer, Solution};
+use crate::error::{Error, SourceLine};
+use std::collections::HashSet;
+use std::fmt;
 
-const INPUT_FILE: &str = "input/day08";
+pub mod assembler;
+pub mod debugger;
+pub mod repair;
+pub mod trace;
 
-#[derive(Clone, Debug)]
-enum Instruction {
+pub const INPUT_FILE: &str = "input/day08";
+
+const DAY: u8 = 8;
+
+pub const REGISTER_NAMES: [&str; 8] = ["acc", "r1", "r2", "r3", "r4", "r5", "r6", "r7"];
+
+// Index into Registers; the boot code only ever uses acc
+pub type Register = usize;
+
+pub const ACC: Register = 0;
+
+pub type Registers = [i32; 8];
+
+// Registers can keep changing without the state ever repeating
+pub const STEP_LIMIT: usize = 1_000_000;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Value {
+  Imm(i32),
+  Reg(Register),
+}
+
+// Jump offsets are relative to the jump itself
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Instruction {
   Nop(i32),
   Acc(i32),
   Jmp(i32),
+  Set(Register, Value),
+  Add(Register, Value),
+  Mul(Register, Value),
+  Jz(Register, i32),
+  Jnz(Register, i32),
 }
 
-type Program = Vec<Instruction>;
+impl fmt::Display for Value {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Value::Imm(val) => write!(f, "{:+}", val),
+      Value::Reg(reg) => write!(f, "{}", REGISTER_NAMES[*reg]),
+    }
+  }
+}
 
-type Execution<'a> = (&'a Program, i32, usize);
+impl fmt::Display for Instruction {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{} {}", self.opcode(), self.operands())
+  }
+}
+
+impl Instruction {
+  pub fn opcode(&self) -> &'static str {
+    match self {
+      Instruction::Nop(_) => "nop",
+      Instruction::Acc(_) => "acc",
+      Instruction::Jmp(_) => "jmp",
+      Instruction::Set(_, _) => "set",
+      Instruction::Add(_, _) => "add",
+      Instruction::Mul(_, _) => "mul",
+      Instruction::Jz(_, _) => "jz",
+      Instruction::Jnz(_, _) => "jnz",
+    }
+  }
 
-pub fn solve() -> Result<(), Box<dyn Error>> {
-  let program = parse_input()?;
+  // The operands as written in the assembler syntax
+  pub fn operands(&self) -> String {
+    match self {
+      Instruction::Nop(val) | Instruction::Acc(val) | Instruction::Jmp(val) => format!("{:+}", val),
+      Instruction::Set(reg, value) | Instruction::Add(reg, value) | Instruction::Mul(reg, value) => {
+        format!("{} {}", REGISTER_NAMES[*reg], value)
+      },
+      Instruction::Jz(reg, offset) | Instruction::Jnz(reg, offset) => format!("{} {:+}", REGISTER_NAMES[*reg], offset),
+    }
+  }
 
-  solve_part_one(&program);
-  solve_part_two(&program);
+  // Whether the next ip depends on the registers
+  pub fn is_conditional(&self) -> bool {
+    matches!(self, Instruction::Jz(_, _) | Instruction::Jnz(_, _))
+  }
 
-  return Ok(());
+  // The repair from part two swaps a single nop and jmp
+  pub fn flipped(&self) -> Option<Instruction> {
+    match *self {
+      Instruction::Nop(val) => Some(Instruction::Jmp(val)),
+      Instruction::Jmp(val) => Some(Instruction::Nop(val)),
+      _ => None,
+    }
+  }
 }
 
-fn solve_part_one(program: &Program) {
+pub type Program = Vec<Instruction>;
+
+pub type Execution<'a> = (&'a Program, Registers, usize);
+
+// One executed instruction: (step, ip, instruction, acc before, acc after)
+pub type TraceEntry<'a> = (usize, usize, &'a Instruction, i32, i32);
+
+pub struct Day08;
+
+impl Solution for Day08 {
+  type Input = Program;
+
+  fn parse(input: &InputSource) -> Result<Self::Input, Error> {
+    parse_input(input)
+  }
+
+  fn part_one(program: &Self::Input) -> Result<Answer, Error> {
+    Ok(solve_part_one(program))
+  }
+
+  fn part_two(program: &Self::Input) -> Result<Answer, Error> {
+    Ok(solve_part_two(program))
+  }
+}
+
+fn solve_part_one(program: &Program) -> Answer {
   let (_terminated, acc) = run_program(program);
 
-  println!("Part 1: {}", acc);
+  acc.into()
 }
 
-fn solve_part_two(program: &Program) {
-  for i in 0..program.len() {
-    let instruction = program.get(i).unwrap();
-    match *instruction {
-      Instruction::Nop(val) => {
-        let mut modded_program: Program = (*program).clone();
-        modded_program[i] = Instruction::Jmp(val);
-        let (terminated, acc) = run_program(&modded_program);
-        if terminated {
-          println!("Part 2: {}", acc);
-        }
-      },
-      Instruction::Jmp(val) => {
-        let mut modded_program: Program = (*program).clone();
-        modded_program[i] = Instruction::Nop(val);
-        let (terminated, acc) = run_program(&modded_program);
-        if terminated {
-          println!("Part 2: {}", acc);
-        }
-      },
-      _ => (),
-    }
-  }
+fn solve_part_two(program: &Program) -> Answer {
+  repair::find_repair(program).map(|(_, acc)| acc).into()
+}
+
+pub fn run_program(program: &Program) -> (bool, i32) {
+  trace_program(program, |_| ())
 }
 
-fn run_program(program: &Program) -> (bool, i32) {
-  let mut execution: Execution = (program, 0, 0);
-  let mut inst_count: HashMap<usize, u32> = HashMap::new();
+// Runs like run_program, passing every executed instruction to on_step.
+//
+// Without conditional jumps the path doesn't depend on the registers, so the program loops as
+// soon as an instruction is about to run a second time. Otherwise it loops when the whole
+// machine state repeats, and counts as looping once it has run STEP_LIMIT instructions.
+pub fn trace_program(program: &Program, mut on_step: impl FnMut(TraceEntry)) -> (bool, i32) {
+  let conditional = program.iter().any(Instruction::is_conditional);
+  let mut execution: Execution = (program, [0; 8], 0);
+  let mut visited: HashSet<usize> = HashSet::new();
+  let mut states: HashSet<(usize, Registers)> = HashSet::new();
+  let mut step = 0;
 
   loop {
-    let inst = execution.2;
+    let (_, registers, inst) = execution;
     if inst >= program.len() {
-      return (true, execution.1);
-    } else if let Some(_x) = inst_count.get(&inst) {
-      return (false, execution.1)
+      return (true, registers[ACC]);
+    }
+    let repeated = if conditional {
+      step >= STEP_LIMIT || !states.insert((inst, registers))
     } else {
-      inst_count.insert(inst, 1);
+      !visited.insert(inst)
+    };
+    if repeated {
+      return (false, registers[ACC]);
     }
+
     execution = execute_next(execution);
+    step += 1;
+    on_step((step, inst, &program[inst], registers[ACC], execution.1[ACC]));
   }
 }
 
-fn execute_next(execution: Execution) -> Execution {
-  let (program, acc, inst) = execution;
-  let current_instruction = program.get(inst).unwrap();
-
-  match current_instruction {
-    Instruction::Nop(_val) => (program, acc, inst + 1),
-    Instruction::Acc(val) => (program, acc + val, inst + 1),
-    Instruction::Jmp(val) => (program, acc, (inst as i32 + val) as usize),
+pub fn execute_next(execution: Execution) -> Execution {
+  let (program, mut registers, inst) = execution;
+  let value = |value: Value| match value {
+    Value::Imm(val) => val,
+    Value::Reg(reg) => registers[reg],
+  };
+  let jump = |offset: i32| (inst as i32 + offset) as usize;
+
+  match program[inst] {
+    Instruction::Nop(_val) => (program, registers, inst + 1),
+    Instruction::Acc(val) => {
+      registers[ACC] = registers[ACC].wrapping_add(val);
+      (program, registers, inst + 1)
+    },
+    Instruction::Jmp(val) => (program, registers, jump(val)),
+    Instruction::Set(reg, v) => {
+      registers[reg] = value(v);
+      (program, registers, inst + 1)
+    },
+    Instruction::Add(reg, v) => {
+      registers[reg] = registers[reg].wrapping_add(value(v));
+      (program, registers, inst + 1)
+    },
+    Instruction::Mul(reg, v) => {
+      registers[reg] = registers[reg].wrapping_mul(value(v));
+      (program, registers, inst + 1)
+    },
+    Instruction::Jz(reg, offset) => (program, registers, if registers[reg] == 0 { jump(offset) } else { inst + 1 }),
+    Instruction::Jnz(reg, offset) => (program, registers, if registers[reg] != 0 { jump(offset) } else { inst + 1 }),
   }
 }
 
-fn parse_input() -> Result<Program, Box<dyn Error>> {
-  let lines = helpers::read_lines(INPUT_FILE)?;
+fn parse_input(input: &InputSource) -> Result<Program, Error> {
+  let lines = helpers::read_lines(input)?.collect::<Result<Vec<_>, _>>()?;
 
-  let mut program: Program = vec![];
+  Ok(assembler::assemble(SourceLine::numbered(DAY, &lines))?)
+}
 
-  for line in lines {
-    let line = line?;
-    let mut parts = line.split_whitespace();
+#[cfg(test)]
+mod tests {
+  use super::*;
 
-    let operation = parts.next().unwrap().to_string();
-    let arg = parts.next().unwrap().parse::<i32>()?;
+  #[test]
+  fn solves_example() {
+    let input = Day08::parse(&InputSource::File("input/test/day08".into())).unwrap();
+    assert_eq!(Day08::part_one(&input).unwrap(), Answer::Int(5));
+    assert_eq!(Day08::part_two(&input).unwrap(), Answer::Int(8));
+  }
 
-    match operation.as_str() {
-      "acc" => program.push(Instruction::Acc(arg)),
-      "jmp" => program.push(Instruction::Jmp(arg)),
-      "nop" => program.push(Instruction::Nop(arg)),
-      _ => (),
+  #[test]
+  fn reports_malformed_input() {
+    match Day08::parse(&InputSource::Text("nop +0\nacc +x\n".into())) {
+      Err(Error::Parse(e)) => assert_eq!((e.day, e.line, e.column), (8, 2, 5)),
+      _ => panic!("expected a parse error"),
     }
-
   }
-
-  return Ok(program);
-}
\ No newline at end of file
+}
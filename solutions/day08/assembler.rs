@@ -0,0 +1,144 @@
+use super::{Instruction, Program, Register, Value, REGISTER_NAMES};
+use crate::error::{ParseError, SourceLine};
+use std::collections::HashMap;
+
+// One instruction per line, optionally after a `label:`. A label can also stand on its own line,
+// naming the next instruction, and jumps take either a relative offset or a label. `#` starts a
+// comment and blank lines are skipped.
+//
+//   set r1 +3
+//   loop: acc +2
+//     add r1 -1
+//     jnz r1 loop
+pub fn assemble<'a>(lines: impl IntoIterator<Item=SourceLine<'a>>) -> Result<Program, ParseError> {
+  let mut labels: HashMap<&str, usize> = HashMap::new();
+  let mut instructions: Vec<(SourceLine, &str)> = vec![];
+
+  for line in lines {
+    let text = line.text;
+    let mut code = text.split('#').next().unwrap_or(text);
+
+    if let Some(colon) = code.find(':') {
+      let label = code[..colon].trim();
+      if !is_label(label) {
+        return Err(line.error(label, &format!("invalid label '{}'", label)));
+      }
+      if labels.insert(label, instructions.len()).is_some() {
+        return Err(line.error(label, &format!("label '{}' is already defined", label)));
+      }
+      code = &code[colon + 1..];
+    }
+
+    if !code.trim().is_empty() {
+      instructions.push((line, code));
+    }
+  }
+
+  let mut program: Program = vec![];
+  for (ip, (line, code)) in instructions.iter().enumerate() {
+    program.push(parse_instruction(line, code, ip, &labels)?);
+  }
+
+  Ok(program)
+}
+
+// The inverse of assemble, one instruction per line with offsets instead of labels
+pub fn disassemble(program: &Program) -> String {
+  program.iter().map(|instruction| format!("{}\n", instruction)).collect()
+}
+
+fn parse_instruction(line: &SourceLine, code: &str, ip: usize, labels: &HashMap<&str, usize>) -> Result<Instruction, ParseError> {
+  let mut parts = code.split_whitespace();
+  let opcode = line.expect(parts.next(), "expected an opcode")?;
+  let operands = parts.collect::<Vec<_>>();
+
+  let count = match opcode {
+    "nop" | "acc" | "jmp" => 1,
+    "set" | "add" | "mul" | "jz" | "jnz" => 2,
+    _ => return Err(line.error(opcode, &format!("unknown opcode '{}'", opcode))),
+  };
+  if operands.len() > count {
+    return Err(line.error(operands[count], "unexpected operand"));
+  }
+  let operand = |i: usize| line.expect(operands.get(i).copied(), &format!("'{}' expects {} operand(s)", opcode, count));
+
+  let instruction = match opcode {
+    "nop" => Instruction::Nop(parse_offset(line, operand(0)?, ip, labels)?),
+    "acc" => Instruction::Acc(line.parse(operand(0)?)?),
+    "jmp" => Instruction::Jmp(parse_offset(line, operand(0)?, ip, labels)?),
+    "set" => Instruction::Set(parse_register(line, operand(0)?)?, parse_value(line, operand(1)?)?),
+    "add" => Instruction::Add(parse_register(line, operand(0)?)?, parse_value(line, operand(1)?)?),
+    "mul" => Instruction::Mul(parse_register(line, operand(0)?)?, parse_value(line, operand(1)?)?),
+    "jz" => Instruction::Jz(parse_register(line, operand(0)?)?, parse_offset(line, operand(1)?, ip, labels)?),
+    _ => Instruction::Jnz(parse_register(line, operand(0)?)?, parse_offset(line, operand(1)?, ip, labels)?),
+  };
+
+  Ok(instruction)
+}
+
+fn is_label(name: &str) -> bool {
+  let mut chars = name.chars();
+  chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+    && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+    && !REGISTER_NAMES.contains(&name)
+}
+
+fn parse_register(line: &SourceLine, part: &str) -> Result<Register, ParseError> {
+  REGISTER_NAMES.iter().position(|&name| name == part)
+    .ok_or_else(|| line.error(part, &format!("unknown register '{}'", part)))
+}
+
+fn parse_value(line: &SourceLine, part: &str) -> Result<Value, ParseError> {
+  if part.starts_with(|c: char| c.is_ascii_alphabetic()) {
+    Ok(Value::Reg(parse_register(line, part)?))
+  } else {
+    Ok(Value::Imm(line.parse(part)?))
+  }
+}
+
+// Labels become offsets relative to the jumping instruction
+fn parse_offset(line: &SourceLine, part: &str, ip: usize, labels: &HashMap<&str, usize>) -> Result<i32, ParseError> {
+  if is_label(part) {
+    labels.get(part)
+      .map(|&target| target as i32 - ip as i32)
+      .ok_or_else(|| line.error(part, &format!("unknown label '{}'", part)))
+  } else {
+    line.parse(part)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::day08::{run_program, ACC};
+
+  fn assemble_text(text: &str) -> Result<Program, ParseError> {
+    let lines: Vec<String> = text.lines().map(String::from).collect();
+    assemble(SourceLine::numbered(8, &lines))
+  }
+
+  #[test]
+  fn resolves_labels_and_round_trips() {
+    let source = "  set r1 +3  # counter\nloop: acc +2\n  add r1 -1\n  jnz r1 loop\ndone:\n  mul acc +2\n";
+    let program = assemble_text(source).unwrap();
+    assert_eq!(program[3], Instruction::Jnz(1, -2));
+    assert_eq!(program[4], Instruction::Mul(ACC, Value::Imm(2)));
+    assert_eq!(run_program(&program), (true, 12));
+
+    let text = disassemble(&program);
+    assert_eq!(text.lines().nth(3), Some("jnz r1 -2"));
+    assert_eq!(assemble_text(&text).unwrap(), program);
+  }
+
+  #[test]
+  fn reports_unknown_names() {
+    let error = assemble_text("nop +0\n  hcf +1\n").unwrap_err();
+    assert_eq!((error.line, error.column, error.message.as_str()), (2, 3, "unknown opcode 'hcf'"));
+    let error = assemble_text("jz r1 nowhere\n").unwrap_err();
+    assert_eq!((error.column, error.message.as_str()), (7, "unknown label 'nowhere'"));
+    let error = assemble_text("add r9 +1\n").unwrap_err();
+    assert_eq!(error.column, 5);
+    let error = assemble_text("jmp +1 +2\n").unwrap_err();
+    assert_eq!(error.column, 8);
+  }
+}
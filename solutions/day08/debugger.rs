@@ -0,0 +1,355 @@
+use super::{execute_next, Execution, Instruction, Program, Registers, ACC, REGISTER_NAMES, STEP_LIMIT};
+use std::collections::{BTreeSet, HashSet, VecDeque};
+use std::io::{self, BufRead, Write};
+
+pub const BACKTRACE_SIZE: usize = 16;
+
+const LIST_SIZE: usize = 5;
+
+const HELP: &str = "Commands:
+  s, step [n]        execute the next n instructions (default 1)
+  c, continue        run until a breakpoint, a loop or the end
+  b, break [ip]      set a breakpoint at ip, or list the breakpoints
+  d, delete <ip>     remove the breakpoint at ip
+  w, watch           toggle printing ip and acc after every instruction
+  bt, backtrace [n]  show the last n jumps
+  p, print           show ip, the registers and the next instruction
+  l, list [n]        show the n instructions from ip
+  r, reset           restart the program, keeping the breakpoints
+  h, help            show this message
+  q, quit";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Stop {
+  Stepped,
+  Breakpoint(usize),
+  // The next instruction has already been executed once (in the same state, if the program has
+  // conditional jumps), so the program never terminates
+  Loop(usize),
+  Terminated,
+}
+
+// (step, from ip, to ip)
+pub type Jump = (usize, usize, usize);
+
+pub struct Debugger<'a> {
+  execution: Execution<'a>,
+  steps: usize,
+  breakpoints: BTreeSet<usize>,
+  visited: HashSet<usize>,
+  states: HashSet<(usize, Registers)>,
+  conditional: bool,
+  jumps: VecDeque<Jump>,
+  backtrace_size: usize,
+}
+
+impl<'a> Debugger<'a> {
+  pub fn new(program: &'a Program, backtrace_size: usize) -> Debugger<'a> {
+    Debugger {
+      execution: (program, [0; 8], 0),
+      steps: 0,
+      breakpoints: BTreeSet::new(),
+      visited: HashSet::new(),
+      states: HashSet::new(),
+      conditional: program.iter().any(Instruction::is_conditional),
+      jumps: VecDeque::new(),
+      backtrace_size,
+    }
+  }
+
+  pub fn program(&self) -> &'a Program {
+    self.execution.0
+  }
+
+  pub fn acc(&self) -> i32 {
+    self.execution.1[ACC]
+  }
+
+  pub fn registers(&self) -> &Registers {
+    &self.execution.1
+  }
+
+  pub fn ip(&self) -> usize {
+    self.execution.2
+  }
+
+  pub fn steps(&self) -> usize {
+    self.steps
+  }
+
+  pub fn terminated(&self) -> bool {
+    self.ip() >= self.program().len()
+  }
+
+  pub fn current(&self) -> Option<&'a Instruction> {
+    self.program().get(self.ip())
+  }
+
+  pub fn breakpoints(&self) -> impl Iterator<Item=&usize> {
+    self.breakpoints.iter()
+  }
+
+  pub fn set_breakpoint(&mut self, ip: usize) -> bool {
+    self.breakpoints.insert(ip)
+  }
+
+  pub fn clear_breakpoint(&mut self, ip: usize) -> bool {
+    self.breakpoints.remove(&ip)
+  }
+
+  // Oldest first
+  pub fn backtrace(&self) -> impl Iterator<Item=&Jump> {
+    self.jumps.iter()
+  }
+
+  pub fn reset(&mut self) {
+    self.execution = (self.program(), [0; 8], 0);
+    self.steps = 0;
+    self.visited.clear();
+    self.states.clear();
+    self.jumps.clear();
+  }
+
+  pub fn step(&mut self) -> Stop {
+    if self.terminated() {
+      return Stop::Terminated;
+    }
+
+    let from = self.ip();
+    self.visited.insert(from);
+    if self.conditional {
+      self.states.insert((from, *self.registers()));
+    }
+    self.execution = execute_next(self.execution);
+    self.steps += 1;
+
+    if self.ip() != from + 1 {
+      self.jumps.push_back((self.steps, from, self.ip()));
+      if self.jumps.len() > self.backtrace_size {
+        self.jumps.pop_front();
+      }
+    }
+
+    if self.terminated() { Stop::Terminated } else { Stop::Stepped }
+  }
+
+  // Always executes at least one instruction, so resuming from a breakpoint moves on
+  pub fn resume(&mut self, mut on_step: impl FnMut(&Debugger)) -> Stop {
+    loop {
+      let stop = self.step();
+      on_step(self);
+      let ip = self.ip();
+      if stop == Stop::Terminated {
+        return stop;
+      } else if self.breakpoints.contains(&ip) {
+        return Stop::Breakpoint(ip);
+      } else if self.looping() {
+        return Stop::Loop(ip);
+      }
+    }
+  }
+
+  // Without conditional jumps the path can't change, so revisiting an instruction is enough
+  fn looping(&self) -> bool {
+    if self.conditional {
+      self.steps >= STEP_LIMIT || self.states.contains(&(self.ip(), *self.registers()))
+    } else {
+      self.visited.contains(&self.ip())
+    }
+  }
+}
+
+pub fn repl(program: &Program, input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+  let mut debugger = Debugger::new(program, BACKTRACE_SIZE);
+  let mut watch = false;
+
+  writeln!(output, "{} instructions loaded, type 'help' for commands", program.len())?;
+  print_state(&mut output, &debugger)?;
+  prompt(&mut output)?;
+
+  for line in input.lines() {
+    let line = line?;
+    let words = line.split_whitespace().collect::<Vec<_>>();
+    let (command, args) = match words.split_first() {
+      Some((command, args)) => (*command, args),
+      None => {
+        prompt(&mut output)?;
+        continue;
+      },
+    };
+
+    match (command, args) {
+      ("s", _) | ("step", _) => match parse_arg(args, 1) {
+        Ok(n) => {
+          let mut stop = Stop::Stepped;
+          for _ in 0..n {
+            stop = debugger.step();
+            if watch {
+              print_watch(&mut output, &debugger)?;
+            }
+            if stop == Stop::Terminated {
+              break;
+            }
+          }
+          print_stop(&mut output, &debugger, stop)?;
+        },
+        Err(e) => writeln!(output, "{}", e)?,
+      },
+      ("c", []) | ("continue", []) => {
+        let mut watched = vec![];
+        let stop = debugger.resume(|d| if watch { watched.push((d.steps(), d.ip(), d.acc())) });
+        for (step, ip, acc) in watched {
+          writeln!(output, "  step {:>5}  ip {:>4}  acc {}", step, ip, acc)?;
+        }
+        print_stop(&mut output, &debugger, stop)?;
+      },
+      ("b", []) | ("break", []) => {
+        let breakpoints = debugger.breakpoints().map(|ip| ip.to_string()).collect::<Vec<_>>();
+        if breakpoints.is_empty() {
+          writeln!(output, "No breakpoints")?;
+        } else {
+          writeln!(output, "Breakpoints: {}", breakpoints.join(", "))?;
+        }
+      },
+      ("b", [_]) | ("break", [_]) => match parse_ip(args, program) {
+        Ok(ip) => {
+          debugger.set_breakpoint(ip);
+          writeln!(output, "Breakpoint at {}", ip)?;
+        },
+        Err(e) => writeln!(output, "{}", e)?,
+      },
+      ("d", [_]) | ("delete", [_]) => match parse_ip(args, program) {
+        Ok(ip) if debugger.clear_breakpoint(ip) => writeln!(output, "Removed breakpoint at {}", ip)?,
+        Ok(ip) => writeln!(output, "No breakpoint at {}", ip)?,
+        Err(e) => writeln!(output, "{}", e)?,
+      },
+      ("w", []) | ("watch", []) => {
+        watch = !watch;
+        writeln!(output, "Watch {}", if watch { "on" } else { "off" })?;
+      },
+      ("bt", _) | ("backtrace", _) => match parse_arg(args, BACKTRACE_SIZE) {
+        Ok(n) => {
+          let jumps = debugger.backtrace().collect::<Vec<_>>();
+          if jumps.is_empty() {
+            writeln!(output, "No jumps yet")?;
+          }
+          for (step, from, to) in jumps.iter().skip(jumps.len().saturating_sub(n)) {
+            writeln!(output, "  step {:>5}  {:>4} -> {}", step, from, to)?;
+          }
+        },
+        Err(e) => writeln!(output, "{}", e)?,
+      },
+      ("p", []) | ("print", []) => print_state(&mut output, &debugger)?,
+      ("l", _) | ("list", _) => match parse_arg(args, LIST_SIZE) {
+        Ok(n) => {
+          for (ip, instruction) in program.iter().enumerate().skip(debugger.ip()).take(n) {
+            let marker = if debugger.breakpoints().any(|&b| b == ip) { '*' } else { ' ' };
+            writeln!(output, "{}{:>4}: {}", marker, ip, instruction)?;
+          }
+        },
+        Err(e) => writeln!(output, "{}", e)?,
+      },
+      ("r", []) | ("reset", []) => {
+        debugger.reset();
+        print_state(&mut output, &debugger)?;
+      },
+      ("h", []) | ("help", []) => writeln!(output, "{}", HELP)?,
+      ("q", []) | ("quit", []) => return Ok(()),
+      _ => writeln!(output, "Unknown command '{}', type 'help' for commands", line.trim())?,
+    }
+    prompt(&mut output)?;
+  }
+
+  writeln!(output)?;
+  Ok(())
+}
+
+fn prompt(output: &mut impl Write) -> io::Result<()> {
+  write!(output, "(day08) ")?;
+  output.flush()
+}
+
+// Other registers are only shown once they have been used
+fn print_state(output: &mut impl Write, debugger: &Debugger) -> io::Result<()> {
+  let registers = debugger.registers().iter().enumerate()
+    .filter(|&(reg, &val)| reg != ACC && val != 0)
+    .map(|(reg, val)| format!("  {} {}", REGISTER_NAMES[reg], val))
+    .collect::<String>();
+  match debugger.current() {
+    Some(instruction) => writeln!(output, "ip {}  acc {}{}  next: {}", debugger.ip(), debugger.acc(), registers, instruction),
+    None => writeln!(output, "ip {}  acc {}{}  (end of program)", debugger.ip(), debugger.acc(), registers),
+  }
+}
+
+fn print_watch(output: &mut impl Write, debugger: &Debugger) -> io::Result<()> {
+  writeln!(output, "  step {:>5}  ip {:>4}  acc {}", debugger.steps(), debugger.ip(), debugger.acc())
+}
+
+fn print_stop(output: &mut impl Write, debugger: &Debugger, stop: Stop) -> io::Result<()> {
+  match stop {
+    Stop::Stepped => (),
+    Stop::Breakpoint(ip) => writeln!(output, "Breakpoint at {}", ip)?,
+    Stop::Loop(ip) => writeln!(output, "Instruction {} was already executed, the program loops", ip)?,
+    Stop::Terminated => writeln!(output, "Program terminated after {} steps", debugger.steps())?,
+  }
+  print_state(output, debugger)
+}
+
+fn parse_arg(args: &[&str], default: usize) -> Result<usize, String> {
+  match args {
+    [] => Ok(default),
+    [arg] => arg.parse::<usize>().map_err(|_| format!("Expected a number, got '{}'", arg)),
+    _ => Err("Expected at most one argument".to_string()),
+  }
+}
+
+fn parse_ip(args: &[&str], program: &Program) -> Result<usize, String> {
+  match parse_arg(args, 0)? {
+    ip if ip < program.len() => Ok(ip),
+    ip => Err(format!("{} is outside the program (0-{})", ip, program.len().saturating_sub(1))),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::day08::Day08;
+  use crate::helpers::InputSource;
+  use crate::solution::Solution;
+
+  fn example() -> Program {
+    Day08::parse(&InputSource::File("input/test/day08".into())).unwrap()
+  }
+
+  #[test]
+  fn stops_at_breakpoints_and_loops() {
+    let program = example();
+    let mut debugger = Debugger::new(&program, 2);
+    debugger.set_breakpoint(6);
+    assert_eq!(debugger.resume(|_| ()), Stop::Breakpoint(6));
+    assert_eq!(debugger.acc(), 1);
+    assert_eq!(debugger.resume(|_| ()), Stop::Loop(1));
+    assert_eq!(debugger.acc(), 5);
+    assert_eq!(debugger.backtrace().cloned().collect::<Vec<_>>(), vec![(5, 7, 3), (7, 4, 1)]);
+  }
+
+  #[test]
+  fn follows_conditional_jumps() {
+    let program = Day08::parse(&InputSource::Text("set r1 +2\nadd r1 -1\njnz r1 -1\njmp +0\n".into())).unwrap();
+    let mut debugger = Debugger::new(&program, BACKTRACE_SIZE);
+    assert_eq!(debugger.resume(|_| ()), Stop::Loop(3));
+    assert_eq!(debugger.registers()[1], 0);
+    assert_eq!(debugger.backtrace().cloned().collect::<Vec<_>>(), vec![(3, 2, 1), (6, 3, 3)]);
+  }
+
+  #[test]
+  fn runs_commands_from_input() {
+    let program = example();
+    let mut output = vec![];
+    repl(&program, "step 2\nb 7\nc\nbt\nq\n".as_bytes(), &mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains("ip 2  acc 1  next: jmp +4"));
+    assert!(output.contains("Breakpoint at 7\nip 7  acc 2  next: jmp -4"));
+    assert!(output.contains("step     3     2 -> 6"));
+  }
+}
@@ -0,0 +1,178 @@
+use super::{run_program, trace_program, Instruction, Program};
+use std::collections::{HashSet, VecDeque};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Repairs {
+  NotNeeded(i32),
+  // The nop/jmp flips on the looping path that make the program terminate, in execution order,
+  // and how many instructions run before the loop repeats
+  Flips(Vec<usize>, usize),
+}
+
+// The index executed after `instruction` at `ip`, or None when execution leaves the program
+fn next_ip(program: &Program, ip: usize, instruction: &Instruction) -> Option<usize> {
+  let next = match instruction {
+    Instruction::Jmp(val) => ip as i64 + *val as i64,
+    _ => ip as i64 + 1,
+  };
+  if next >= 0 && (next as usize) < program.len() { Some(next as usize) } else { None }
+}
+
+// Which instructions terminate when execution starts there, found by walking back from the exits.
+// Only meaningful for programs without conditional jumps.
+pub fn terminating_instructions(program: &Program) -> Vec<bool> {
+  let mut predecessors = vec![vec![]; program.len()];
+  let mut terminates = vec![false; program.len()];
+  let mut queue = VecDeque::new();
+
+  for (ip, instruction) in program.iter().enumerate() {
+    match next_ip(program, ip, instruction) {
+      Some(next) => predecessors[next].push(ip),
+      None => {
+        terminates[ip] = true;
+        queue.push_back(ip);
+      },
+    }
+  }
+
+  while let Some(ip) = queue.pop_front() {
+    for &predecessor in &predecessors[ip] {
+      if !terminates[predecessor] {
+        terminates[predecessor] = true;
+        queue.push_back(predecessor);
+      }
+    }
+  }
+
+  terminates
+}
+
+// A flip only matters if it is executed, so only the looping path needs checking. The path
+// never reaches the end, so a flipped instruction can't be part of its own way out.
+pub fn find_repairs(program: &Program) -> Repairs {
+  if program.iter().any(Instruction::is_conditional) {
+    return find_repairs_by_running(program);
+  }
+
+  let terminates = terminating_instructions(program);
+  if program.is_empty() || terminates[0] {
+    return Repairs::NotNeeded(run_program(program).1);
+  }
+
+  let mut visited = vec![false; program.len()];
+  let mut flips = vec![];
+  let mut executed = 0;
+  let mut ip = Some(0);
+  while let Some(i) = ip {
+    if visited[i] {
+      break;
+    }
+    visited[i] = true;
+    executed += 1;
+
+    if let Some(flipped) = program[i].flipped() {
+      // Leaving the program terminates straight away
+      let flip_terminates = match next_ip(program, i, &flipped) {
+        Some(next) => terminates[next],
+        None => true,
+      };
+      if flip_terminates {
+        flips.push(i);
+      }
+    }
+    ip = next_ip(program, i, &program[i]);
+  }
+
+  Repairs::Flips(flips, executed)
+}
+
+// With conditional jumps the path depends on the registers, so every executed nop/jmp is tried
+fn find_repairs_by_running(program: &Program) -> Repairs {
+  let mut executed = vec![];
+  let (terminated, acc) = trace_program(program, |(_, ip, _, _, _)| executed.push(ip));
+  if terminated {
+    return Repairs::NotNeeded(acc);
+  }
+
+  let mut tried = HashSet::new();
+  let flips = executed.iter().copied()
+    .filter(|&ip| tried.insert(ip) && program[ip].flipped().is_some())
+    .filter(|&ip| run_program(&patched(program, ip)).0)
+    .collect();
+
+  Repairs::Flips(flips, executed.len())
+}
+
+fn patched(program: &Program, ip: usize) -> Program {
+  let mut patched = program.clone();
+  if let Some(flipped) = program[ip].flipped() {
+    patched[ip] = flipped;
+  }
+  patched
+}
+
+// The flip that fixes the loop and the final acc, if exactly one flip does
+pub fn find_repair(program: &Program) -> Option<(usize, i32)> {
+  match find_repairs(program) {
+    Repairs::Flips(flips, _) if flips.len() == 1 => Some((flips[0], run_program(&patched(program, flips[0])).1)),
+    _ => None,
+  }
+}
+
+pub fn explain(program: &Program) -> String {
+  match find_repairs(program) {
+    Repairs::NotNeeded(acc) => format!("The program already terminates, with acc {}", acc),
+    Repairs::Flips(flips, executed) if flips.is_empty() => format!(
+      "No single flip fixes the loop: {} instructions run before it repeats, and flipping none of their nop/jmp reaches the end",
+      executed,
+    ),
+    Repairs::Flips(flips, _) => {
+      let fixes = flips.iter().map(|&ip| {
+        let patched = patched(program, ip);
+        let (_, acc) = run_program(&patched);
+        format!("flip {} ({} -> {}) terminates with acc {}", ip, program[ip], patched[ip], acc)
+      }).collect::<Vec<_>>();
+
+      if fixes.len() == 1 {
+        format!("Fixed: {}", fixes[0])
+      } else {
+        format!("{} different flips fix the loop:\n  {}", fixes.len(), fixes.join("\n  "))
+      }
+    },
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::day08::Day08;
+  use crate::helpers::InputSource;
+  use crate::solution::Solution;
+
+  fn program(text: &str) -> Program {
+    Day08::parse(&InputSource::Text(text.to_string())).unwrap()
+  }
+
+  #[test]
+  fn finds_the_single_repair() {
+    let program = Day08::parse(&InputSource::File("input/test/day08".into())).unwrap();
+    assert_eq!(find_repairs(&program), Repairs::Flips(vec![7], 7));
+    assert_eq!(find_repair(&program), Some((7, 8)));
+    assert_eq!(explain(&program), "Fixed: flip 7 (jmp -4 -> nop -4) terminates with acc 8");
+  }
+
+  #[test]
+  fn explains_zero_or_several_repairs() {
+    assert_eq!(find_repairs(&program("nop +0\njmp -1\nacc +1\njmp -1\n")), Repairs::Flips(vec![], 2));
+    assert_eq!(find_repairs(&program("nop +2\njmp +0\n")), Repairs::Flips(vec![0, 1], 2));
+    assert_eq!(find_repair(&program("nop +2\njmp +0\n")), None);
+    assert_eq!(find_repairs(&program("acc +3\n")), Repairs::NotNeeded(3));
+  }
+
+  #[test]
+  fn runs_flips_with_conditional_jumps() {
+    let program = program("set r1 +1\nacc +2\njnz r1 +2\nacc +5\nnop +0\njmp -3\n");
+    assert_eq!(find_repairs(&program), Repairs::Flips(vec![5], 5));
+    assert_eq!(find_repair(&program), Some((5, 2)));
+  }
+}
@@ -0,0 +1,74 @@
+use super::{trace_program, Program, TraceEntry};
+use std::io::{self, Write};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TraceFormat {
+  JsonLines,
+  Csv,
+}
+
+impl TraceFormat {
+  pub fn from_name(name: &str) -> Option<TraceFormat> {
+    match name {
+      "jsonl" => Some(TraceFormat::JsonLines),
+      "csv" => Some(TraceFormat::Csv),
+      _ => None,
+    }
+  }
+}
+
+// Runs the program, writing one record per executed instruction, and returns what run_program would
+pub fn write_trace(program: &Program, format: TraceFormat, mut output: impl Write) -> io::Result<(bool, i32)> {
+  if format == TraceFormat::Csv {
+    writeln!(output, "step,ip,opcode,operands,acc_before,acc_after")?;
+  }
+
+  let mut result = Ok(());
+  let outcome = trace_program(program, |entry| {
+    if result.is_ok() {
+      result = write_entry(&mut output, format, entry);
+    }
+  });
+
+  result.map(|_| outcome)
+}
+
+fn write_entry(output: &mut impl Write, format: TraceFormat, entry: TraceEntry) -> io::Result<()> {
+  let (step, ip, instruction, acc_before, acc_after) = entry;
+  match format {
+    TraceFormat::JsonLines => writeln!(output,
+      "{{\"step\":{},\"ip\":{},\"opcode\":\"{}\",\"operands\":\"{}\",\"acc_before\":{},\"acc_after\":{}}}",
+      step, ip, instruction.opcode(), instruction.operands(), acc_before, acc_after,
+    ),
+    TraceFormat::Csv => writeln!(output,
+      "{},{},{},{},{},{}",
+      step, ip, instruction.opcode(), instruction.operands(), acc_before, acc_after,
+    ),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::day08::Day08;
+  use crate::helpers::InputSource;
+  use crate::solution::Solution;
+
+  #[test]
+  fn writes_every_executed_instruction() {
+    let program = Day08::parse(&InputSource::File("input/test/day08".into())).unwrap();
+
+    let mut csv = vec![];
+    assert_eq!(write_trace(&program, TraceFormat::Csv, &mut csv).unwrap(), (false, 5));
+    let csv = String::from_utf8(csv).unwrap();
+    let lines = csv.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 8);
+    assert_eq!(lines[2], "2,1,acc,+1,0,1");
+    assert_eq!(lines[7], "7,4,jmp,-3,5,5");
+
+    let mut json = vec![];
+    write_trace(&program, TraceFormat::JsonLines, &mut json).unwrap();
+    let json = String::from_utf8(json).unwrap();
+    assert_eq!(json.lines().next(), Some("{\"step\":1,\"ip\":0,\"opcode\":\"nop\",\"operands\":\"+0\",\"acc_before\":0,\"acc_after\":0}"));
+  }
+}
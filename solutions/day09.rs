@@ -1,31 +1,54 @@
-use crate::helpers;
-use std::error::Error;
+use crate::helpers::{self, InputSource};
+use crate::solution::{Answer, Solution};
+use crate::error::{Error, ParseError, SourceLine};
 
-const INPUT_FILE: &str = "input/day09";
+pub const INPUT_FILE: &str = "input/day09";
+
+const DAY: u8 = 9;
+
+const PREAMBLE: usize = 25;
 
 type PairSum = (u64, u64, u64);
 
-pub fn solve() -> Result<(), Box<dyn Error>> {
-  let mut input_iterator = parse_input()?;
-  let part_one_result = solve_part_one(&mut input_iterator);
-  if let Some(r) = part_one_result {
-    let mut input_iterator = parse_input()?;
-    solve_part_two(r, &mut input_iterator);
+pub struct Day09;
+
+impl Solution for Day09 {
+  type Input = Vec<u64>;
+
+  fn parse(input: &InputSource) -> Result<Self::Input, Error> {
+    parse_input(input)
+  }
+
+  fn part_one(numbers: &Self::Input) -> Result<Answer, Error> {
+    Ok(solve_part_one(numbers))
   }
-  return Ok(());
+
+  fn part_two(numbers: &Self::Input) -> Result<Answer, Error> {
+    Ok(solve_part_two(numbers))
+  }
+}
+
+fn solve_part_one(numbers: &[u64]) -> Answer {
+  find_invalid_number(&mut numbers.iter().copied(), PREAMBLE).into()
 }
 
-fn solve_part_one(input_iterator: &mut impl Iterator<Item=u64>) -> Option<u64> {
+fn solve_part_two(numbers: &[u64]) -> Answer {
+  match find_invalid_number(&mut numbers.iter().copied(), PREAMBLE) {
+    None => Answer::None,
+    Some(target) => find_weakness(target, &mut numbers.iter().copied()).into(),
+  }
+}
+
+fn find_invalid_number(input_iterator: &mut impl Iterator<Item=u64>, preamble: usize) -> Option<u64> {
   let mut base_numbers: Vec<u64> = vec![];
-  for _i in 0..25 {
-    base_numbers.push(input_iterator.next().unwrap());
+  for _i in 0..preamble {
+    base_numbers.push(input_iterator.next()?);
   }
   let mut pair_sums = all_pair_sums(&base_numbers);
 
-  while let Some(number) = input_iterator.next() {
+  for number in input_iterator.by_ref() {
 
     if pair_sums.iter().find(|(sum, _, _)| *sum == number).is_none() {
-      println!("Part 1: {}", number);
       return Some(number);
     }
 
@@ -33,16 +56,16 @@ fn solve_part_one(input_iterator: &mut impl Iterator<Item=u64>) -> Option<u64> {
 
     let new_pair_sums = base_numbers.iter().map(|num| (*num + number, *num, number));
 
-    pair_sums = pair_sums.into_iter().filter(|(_, a, b)| oldest_base_number != *a && oldest_base_number != *b).collect();
+    pair_sums.retain(|(_, a, b)| oldest_base_number != *a && oldest_base_number != *b);
     new_pair_sums.for_each(|ps| pair_sums.push(ps));
 
     base_numbers.push(number);
   }
 
-  return None;
+  None
 }
 
-fn solve_part_two(target: u64, input_iterator: &mut impl Iterator<Item=u64>) {
+fn find_weakness(target: u64, input_iterator: &mut impl Iterator<Item=u64>) -> Option<u64> {
   let mut current_sum = 0;
   let mut current_numbers: Vec<u64> = vec![];
 
@@ -59,13 +82,14 @@ fn solve_part_two(target: u64, input_iterator: &mut impl Iterator<Item=u64>) {
     if current_sum == target {
       let min = current_numbers.iter().min();
       let max = current_numbers.iter().max();
-      println!("Part 2: {}, {:?}", min.unwrap() + max.unwrap(), current_numbers);
-      break;
+      return Some(min.unwrap() + max.unwrap());
     }
   }
+
+  None
 }
 
-fn all_pair_sums(numbers: &Vec<u64>) -> Vec<PairSum> {
+fn all_pair_sums(numbers: &[u64]) -> Vec<PairSum> {
   let mut pair_sums: Vec<PairSum> = vec![]; 
   for i in 0..numbers.len()-1 {
     for j in i..numbers.len() {
@@ -75,7 +99,33 @@ fn all_pair_sums(numbers: &Vec<u64>) -> Vec<PairSum> {
   pair_sums
 }
 
-fn parse_input() -> Result<impl Iterator<Item=u64>, Box<dyn Error>> {
-  let lines = helpers::read_lines(INPUT_FILE)?;
-  Ok(lines.map(|l| l.unwrap().parse::<u64>().unwrap()))
-}
\ No newline at end of file
+fn parse_input(input: &InputSource) -> Result<Vec<u64>, Error> {
+  let lines = helpers::read_lines(input)?;
+  let mut numbers = vec![];
+  for (i, line) in lines.enumerate() {
+    let line = line?;
+    numbers.push(SourceLine::new(DAY, i + 1, &line).parse::<u64>(&line)?);
+  }
+  if numbers.is_empty() {
+    return Err(ParseError::missing_line(DAY, 1, "expected a number").into());
+  }
+  Ok(numbers)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn solves_example() {
+    let input = Day09::parse(&InputSource::File("input/test/day09".into())).unwrap();
+    assert_eq!(find_invalid_number(&mut input.iter().copied(), 5), Some(127));
+    assert_eq!(find_weakness(127, &mut input.iter().copied()), Some(62));
+  }
+
+  #[test]
+  fn rejects_empty_input() {
+    assert!(matches!(Day09::parse(&InputSource::Text(String::new())), Err(Error::Parse(_))));
+    assert_eq!(Day09::part_one(&vec![1, 2, 3]).unwrap(), Answer::None);
+  }
+}
@@ -1,17 +1,31 @@
-use crate::helpers;
-use std::error::Error;
+use crate::helpers::{self, InputSource};
+use crate::solution::{Answer, Solution};
+use crate::error::{Error, ParseError, SourceLine};
 
-const INPUT_FILE: &str = "input/day10";
+pub const INPUT_FILE: &str = "input/day10";
 
-pub fn solve() -> Result<(), Box<dyn Error>> {
-  let chargers = parse_input()?;
-  solve_part_one(&chargers);
-  solve_part_two(&chargers);
-  return Ok(());
+const DAY: u8 = 10;
+
+pub struct Day10;
+
+impl Solution for Day10 {
+  type Input = Vec<u32>;
+
+  fn parse(input: &InputSource) -> Result<Self::Input, Error> {
+    parse_input(input)
+  }
+
+  fn part_one(chargers: &Self::Input) -> Result<Answer, Error> {
+    Ok(solve_part_one(chargers))
+  }
+
+  fn part_two(chargers: &Self::Input) -> Result<Answer, Error> {
+    Ok(solve_part_two(chargers))
+  }
 }
 
-fn solve_part_one(chargers: &Vec<u32>) {
-  let mut chargers_copy = chargers.clone();
+fn solve_part_one(chargers: &[u32]) -> Answer {
+  let mut chargers_copy = chargers.to_vec();
   chargers_copy.sort();
 
   let mut diff_ones = 0;
@@ -28,11 +42,11 @@ fn solve_part_one(chargers: &Vec<u32>) {
     prev = charger;
   }
 
-  println!("Part 1 {}", diff_ones * diff_threes);
+  (diff_ones * diff_threes).into()
 }
 
-fn solve_part_two(chargers: &Vec<u32>) {
-  let mut chargers_copy = chargers.clone();
+fn solve_part_two(chargers: &[u32]) -> Answer {
+  let mut chargers_copy = chargers.to_vec();
   chargers_copy.sort();
 
   let max = chargers_copy[chargers_copy.len()-1];
@@ -52,10 +66,42 @@ fn solve_part_two(chargers: &Vec<u32>) {
     }
   }
 
-  println!("Part 2 {}", arrangement_counts[max as usize]);
+  arrangement_counts[max as usize].into()
 }
 
-fn parse_input() -> Result<Vec<u32>, Box<dyn Error>> {
-  let lines = helpers::read_lines(INPUT_FILE)?;
-  Ok(lines.map(|l| l.unwrap().parse::<u32>().unwrap()).collect())
-}
\ No newline at end of file
+fn parse_input(input: &InputSource) -> Result<Vec<u32>, Error> {
+  let lines = helpers::read_lines(input)?;
+  let mut numbers = vec![];
+  for (i, line) in lines.enumerate() {
+    let line = line?;
+    numbers.push(SourceLine::new(DAY, i + 1, &line).parse::<u32>(&line)?);
+  }
+  if numbers.is_empty() {
+    return Err(ParseError::missing_line(DAY, 1, "expected a joltage rating").into());
+  }
+  Ok(numbers)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn solves_example() {
+    let input = Day10::parse(&InputSource::File("input/test/day10".into())).unwrap();
+    assert_eq!(Day10::part_one(&input).unwrap(), Answer::Int(220));
+    assert_eq!(Day10::part_two(&input).unwrap(), Answer::Int(19208));
+  }
+
+  #[test]
+  fn solves_example_2() {
+    let input = Day10::parse(&InputSource::File("input/test/day10_02".into())).unwrap();
+    assert_eq!(Day10::part_one(&input).unwrap(), Answer::Int(35));
+    assert_eq!(Day10::part_two(&input).unwrap(), Answer::Int(8));
+  }
+
+  #[test]
+  fn rejects_empty_input() {
+    assert!(matches!(Day10::parse(&InputSource::Text(String::new())), Err(Error::Parse(_))));
+  }
+}
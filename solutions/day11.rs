@@ -1,27 +1,61 @@
-use crate::helpers;
-use std::error::Error;
+use crate::helpers::{self, Grid, GridCell, InputSource};
+use crate::helpers::grid::{Point, DIRECTIONS_8};
+use crate::solution::{Answer, Solution};
+use crate::error::{Error, SourceLine};
 
-const INPUT_FILE: &str = "input/day11";
+pub const INPUT_FILE: &str = "input/day11";
+
+const DAY: u8 = 11;
 
 #[derive(Clone, PartialEq)]
-enum SeatState {
+pub enum SeatState {
   Floor,
   Empty,
   Occupied
 }
 
-type Seat = (usize, usize);
+impl GridCell for SeatState {
+  fn from_char(c: char) -> Option<SeatState> {
+    match c {
+      '.' => Some(SeatState::Floor),
+      'L' => Some(SeatState::Empty),
+      '#' => Some(SeatState::Occupied),
+      _ => None,
+    }
+  }
+
+  fn to_char(&self) -> char {
+    match self {
+      SeatState::Floor => '.',
+      SeatState::Empty => 'L',
+      SeatState::Occupied => '#',
+    }
+  }
+}
+
+type Seat = Point;
+
+type Seating = Grid<SeatState>;
+
+pub struct Day11;
 
-type Seating = Vec<Vec<SeatState>>;
+impl Solution for Day11 {
+  type Input = Seating;
 
-pub fn solve() -> Result<(), Box<dyn Error>> {
-  let initial_seating = parse_input()?;
-  solve_part_one(&initial_seating);
-  solve_part_two(&initial_seating);
-  return Ok(());
+  fn parse(input: &InputSource) -> Result<Self::Input, Error> {
+    parse_input(input)
+  }
+
+  fn part_one(initial_seating: &Self::Input) -> Result<Answer, Error> {
+    Ok(solve_part_one(initial_seating))
+  }
+
+  fn part_two(initial_seating: &Self::Input) -> Result<Answer, Error> {
+    Ok(solve_part_two(initial_seating))
+  }
 }
 
-fn solve_part_one(initial_seating: &Seating) {
+fn solve_part_one(initial_seating: &Seating) -> Answer {
   let mut seating = (*initial_seating).clone();
 
   let mut seating_changed = true;
@@ -29,19 +63,10 @@ fn solve_part_one(initial_seating: &Seating) {
     seating_changed = run_one_round(&mut seating, 4, get_occupied_neighbours);
   }
 
-  let mut occupied_seats = 0;
-  for seat_row in seating {
-    for seat in seat_row {
-      if seat == SeatState::Occupied {
-        occupied_seats += 1;
-      }
-    }
-  }
-
-  println!("Part 1: {}", occupied_seats);
+  seating.iter().filter(|&seat| *seat == SeatState::Occupied).count().into()
 }
 
-fn solve_part_two(initial_seating: &Seating) {
+fn solve_part_two(initial_seating: &Seating) -> Answer {
   let mut seating = (*initial_seating).clone();
 
   let mut seating_changed = true;
@@ -49,40 +74,27 @@ fn solve_part_two(initial_seating: &Seating) {
     seating_changed = run_one_round(&mut seating, 5, get_occupied_in_each_direction);
   }
 
-  let mut occupied_seats = 0;
-  for seat_row in seating {
-    for seat in seat_row {
-      if seat == SeatState::Occupied {
-        occupied_seats += 1;
-      }
-    }
-  }
-
-  println!("Part 2: {}", occupied_seats);
+  seating.iter().filter(|&seat| *seat == SeatState::Occupied).count().into()
 }
 
 fn run_one_round<F> (seating: &mut Seating, occupied_limit: u32, neighbour_func: F) -> bool 
 where F: Fn(&Seating, Seat) -> u32 {
   let mut seating_changed = false;
   let mut seats_to_update: Vec<Seat> = vec![];
-  for i in 0..seating.len() {
-    for j in 0..seating.get(i).unwrap().len() {
-      if seating[i][j] != SeatState::Floor {
-        let occupied_neighbours = neighbour_func(seating, (i, j));
-
-        if occupied_neighbours >= occupied_limit && seating[i][j] != SeatState::Empty {
-          seats_to_update.push((i,j));
-          seating_changed = true;
-        } else if occupied_neighbours == 0 && seating[i][j] != SeatState::Occupied {
-          seats_to_update.push((i,j));
-          seating_changed = true;
-        }
+  for seat in seating.points() {
+    if seating[seat] != SeatState::Floor {
+      let occupied_neighbours = neighbour_func(seating, seat);
+
+      if (occupied_neighbours >= occupied_limit && seating[seat] != SeatState::Empty)
+        || (occupied_neighbours == 0 && seating[seat] != SeatState::Occupied) {
+        seats_to_update.push(seat);
+        seating_changed = true;
       }
     }
   }
 
-  for (r, c) in seats_to_update {
-    seating[r][c] = if seating[r][c] == SeatState::Occupied {
+  for seat in seats_to_update {
+    seating[seat] = if seating[seat] == SeatState::Occupied {
       SeatState::Empty
     } else {
       SeatState::Occupied
@@ -92,82 +104,37 @@ where F: Fn(&Seating, Seat) -> u32 {
   seating_changed
 }
 
-fn get_occupied_neighbours (seating: &Seating, (row, col): Seat) -> u32 {
-  let row_max = seating.len() - 1;
-  let col_max = seating.get(0).unwrap().len() - 1;
-  let rows = if row == 0 {
-    0..=1
-  } else if row == row_max {
-    (row_max-1)..=row_max
-  } else {
-    (row-1)..=(row+1)
-  };
-  let cols = if col == 0 {
-    0..=1
-  } else if col == col_max {
-    (col_max-1)..=col_max
-  } else {
-    (col-1)..=(col+1)
-  };
+fn get_occupied_neighbours (seating: &Seating, seat: Seat) -> u32 {
+  seating.neighbours_8(seat).filter(|&p| seating[p] == SeatState::Occupied).count() as u32
+}
 
+fn get_occupied_in_each_direction(seating: &Seating, seat: Seat) -> u32 {
   let mut occupied_seats = 0;
-  for r in rows {
-    for c in cols.clone() {
-      if !(r == row && c == col) && seating[r][c] == SeatState::Occupied {
+  for &dir in DIRECTIONS_8.iter() {
+    let first_seat = seating.ray(seat, dir).find(|&p| seating[p] != SeatState::Floor);
+    if let Some(p) = first_seat {
+      if seating[p] == SeatState::Occupied {
         occupied_seats += 1;
       }
     }
   }
 
-  occupied_seats
-} 
-
-fn get_occupied_in_each_direction(seating: &Seating, (row, col): Seat) -> u32 {
-  let directions: Vec<(i32, i32)> = vec![(-1, 1), (0, 1), (1,1), (-1, 0), (1,0), (-1,-1), (0,-1), (1,-1)];
-
-  let num_rows = seating.len() as i32;
-  let num_cols = seating.get(0).unwrap().len() as i32;
-
-  let mut occupied_seats = 0;
-  for dir in directions {
-    let (mut r, mut c) = (row as i32 + dir.0, col as i32 + dir.1);
-    while r >= 0 && r < num_rows && c >= 0 && c < num_cols {
-      let seat = seating.get(r as usize).unwrap().get(c as usize).unwrap();
-      match seat {
-        SeatState::Floor => {
-          r = r + dir.0;
-          c = c + dir.1;
-        }
-        SeatState::Empty => {
-          break;
-        },
-        SeatState::Occupied => {
-          occupied_seats += 1;
-          break;
-        }
-
-
-      }
-    }   
-  }
-  
   occupied_seats
 }
 
+fn parse_input(input: &InputSource) -> Result<Seating, Error> {
+  let lines = helpers::read_lines(input)?.collect::<Result<Vec<_>, _>>()?;
+  Ok(Grid::parse(SourceLine::numbered(DAY, &lines))?)
+}
 
-fn parse_input() -> Result<Seating, Box<dyn Error>> {
-  let lines = helpers::read_lines(INPUT_FILE)?;
-  let mut seating: Seating = vec![];
-  
-  for line in lines {
-    let seating_row = line?.chars().map(|c| match c {
-      'L' => SeatState::Empty,
-      '#' => SeatState::Occupied,
-      _ => SeatState::Floor
-    }).collect::<Vec<_>>();
+#[cfg(test)]
+mod tests {
+  use super::*;
 
-    seating.push(seating_row);
+  #[test]
+  fn solves_example() {
+    let input = Day11::parse(&InputSource::File("input/test/day11".into())).unwrap();
+    assert_eq!(Day11::part_one(&input).unwrap(), Answer::Int(37));
+    assert_eq!(Day11::part_two(&input).unwrap(), Answer::Int(26));
   }
-
-  Ok(seating)
 }
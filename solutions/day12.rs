@@ -1,7 +1,10 @@
-use crate::helpers;
-use std::error::Error;
+use crate::helpers::{self, InputSource};
+use crate::solution::{Answer, Solution};
+use crate::error::{Error, SourceLine};
 
-const INPUT_FILE: &str = "input/day12";
+pub const INPUT_FILE: &str = "input/day12";
+
+const DAY: u8 = 12;
 
 enum Direction {
   N,
@@ -17,10 +20,7 @@ impl Direction {
       90 => Direction::E,
       180 => Direction::S,
       270 => Direction::W,
-      _ => {
-        println!("Unknown direction! {}", degrees);
-        Direction::N
-      }
+      _ => unreachable!("turns are checked to be multiples of 90 degrees when parsing"),
     }
   }
 
@@ -51,7 +51,7 @@ impl Direction {
   }
 }
 
-enum Action {
+pub enum Action {
   N(i32),
   E(i32),
   S(i32),
@@ -65,33 +65,42 @@ type ShipState = (i32, i32, Direction);
 
 type ShipWaypointState = (i32, i32, i32, i32);
 
-pub fn solve() -> Result<(), Box<dyn Error>> {
-  let actions = parse_input()?;
-  solve_part_one(&actions);
-  solve_part_two(&actions);
-  return Ok(());
+pub struct Day12;
+
+impl Solution for Day12 {
+  type Input = Vec<Action>;
+
+  fn parse(input: &InputSource) -> Result<Self::Input, Error> {
+    parse_input(input)
+  }
+
+  fn part_one(actions: &Self::Input) -> Result<Answer, Error> {
+    Ok(solve_part_one(actions))
+  }
+
+  fn part_two(actions: &Self::Input) -> Result<Answer, Error> {
+    Ok(solve_part_two(actions))
+  }
 }
 
-fn solve_part_one(actions: &Vec<Action>) {
+fn solve_part_one(actions: &Vec<Action>) -> Answer {
   let mut ship = (0, 0, Direction::E);
 
   for action in actions {
     ship = perform_action(ship, action);
   }
 
-  println!("Part 1: {}", ship.0.abs() + ship.1.abs());
+  (ship.0.abs() + ship.1.abs()).into()
 }
 
-fn solve_part_two(actions: &Vec<Action>) {
+fn solve_part_two(actions: &Vec<Action>) -> Answer {
   let mut ship_waypoint = (10, 1, 0, 0);
 
   for action in actions {
     ship_waypoint = perform_waypoint_action(ship_waypoint, action);
-    println!(":::: ({}, {}) -- ({}, {})", ship_waypoint.0, ship_waypoint.1, ship_waypoint.2, ship_waypoint.3);
-
   }
 
-  println!("Part 2: {}", ship_waypoint.2.abs() + ship_waypoint.3.abs());
+  (ship_waypoint.2.abs() + ship_waypoint.3.abs()).into()
 }
 
 fn perform_action ((x, y, dir): ShipState, action: &Action) -> ShipState {
@@ -140,14 +149,16 @@ fn rotate_waypoint_left ((x_w, y_w, x_s, y_s): ShipWaypointState, degrees: i32)
 
 }
 
-fn parse_input() -> Result<Vec<Action>, Box<dyn Error>> {
-  let lines = helpers::read_lines(INPUT_FILE)?;
+fn parse_input(input: &InputSource) -> Result<Vec<Action>, Error> {
+  let lines = helpers::read_lines(input)?;
   let mut actions: Vec<Action> = vec![];
   
-  for line in lines {
+  for (i, line) in lines.enumerate() {
     let line = line?;
-    let (command, val) = line.split_at(1);
-    let val = val.parse::<i32>()?;
+    let source = SourceLine::new(DAY, i + 1, &line);
+    let split = line.chars().next().map_or(0, |c| c.len_utf8());
+    let (command, val) = line.split_at(split);
+    let val = source.parse::<i32>(val)?;
 
     match command {
       "N" => actions.push(Action::N(val)),
@@ -157,9 +168,31 @@ fn parse_input() -> Result<Vec<Action>, Box<dyn Error>> {
       "L" => actions.push(Action::L(val)),
       "R" => actions.push(Action::R(val)),
       "F" => actions.push(Action::F(val)),
-      _ => ()
+      _ => return Err(source.error(command, "unknown action").into()),
+    }
+    if (command == "L" || command == "R") && val % 90 != 0 {
+      return Err(source.error(&line[split..], "turns must be a multiple of 90 degrees").into());
     }
   }
 
   Ok(actions)
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn solves_example() {
+    let input = Day12::parse(&InputSource::File("input/test/day12".into())).unwrap();
+    assert_eq!(Day12::part_one(&input).unwrap(), Answer::Int(25));
+    assert_eq!(Day12::part_two(&input).unwrap(), Answer::Int(286));
+  }
+  #[test]
+  fn rejects_turns_off_the_compass_points() {
+    match Day12::parse(&InputSource::Text("F10\nR45\n".into())) {
+      Err(Error::Parse(e)) => assert_eq!((e.line, e.column, e.message.as_str()), (2, 2, "turns must be a multiple of 90 degrees")),
+      _ => panic!("expected a parse error"),
+    }
+  }
+}
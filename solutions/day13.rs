@@ -1,17 +1,31 @@
-use crate::helpers;
-use std::error::Error;
+use crate::helpers::{self, InputSource};
+use crate::solution::{Answer, Solution};
+use crate::error::{Error, ParseError, SourceLine};
 
-const INPUT_FILE: &str = "input/day13";
+pub const INPUT_FILE: &str = "input/day13";
 
+const DAY: u8 = 13;
 
-pub fn solve() -> Result<(), Box<dyn Error>> {
-  let (depart_time, bus_times) = parse_input()?;
-  solve_part_one(depart_time, &bus_times);
-  solve_part_two(&bus_times);
-  return Ok(());
+
+pub struct Day13;
+
+impl Solution for Day13 {
+  type Input = (u128, Vec<Option<u128>>);
+
+  fn parse(input: &InputSource) -> Result<Self::Input, Error> {
+    parse_input(input)
+  }
+
+  fn part_one((depart_time, bus_times): &Self::Input) -> Result<Answer, Error> {
+    Ok(solve_part_one(*depart_time, bus_times))
+  }
+
+  fn part_two((_, bus_times): &Self::Input) -> Result<Answer, Error> {
+    Ok(solve_part_two(bus_times))
+  }
 }
 
-fn solve_part_one(depart_time: u128, bus_times: &Vec<Option<u128>>) {
+fn solve_part_one(depart_time: u128, bus_times: &[Option<u128>]) -> Answer {
   let (id, bus_time) = bus_times.iter().fold(None, |min, t| {
     match t {
       None => min,
@@ -29,26 +43,24 @@ fn solve_part_one(depart_time: u128, bus_times: &Vec<Option<u128>>) {
     }
   }).unwrap();
 
-  println!("Part 1: {}", (bus_time - depart_time) * id);
+  ((bus_time - depart_time) * id).into()
 }
 
-fn solve_part_two(bus_times: &Vec<Option<u128>>) {
+fn solve_part_two(bus_times: &[Option<u128>]) -> Answer {
   let mut increment: u128 = 1;
   let mut time: u128 = 0;
-  for i in 0..bus_times.len() {
-    let bus_time = bus_times[i];
+  for (i, &bus_time) in bus_times.iter().enumerate() {
     match bus_time {
       None => (),
       Some(t) => {
         while time.rem_euclid(t) != (t as i128 - i as i128).rem_euclid(t as i128) as u128 {
-          println!("Tst {}, {} ---- {}, {}, {}", time.rem_euclid(t), i, increment, time, t);
           time += increment;
         }
         increment = least_common_multiple(increment, t);
       }
     }
   }
-  println!("Part 2: {}", time);
+  time.into()
 }
 
 fn next_depart_time(bus_time: u128, from: u128) -> u128 {
@@ -72,13 +84,25 @@ fn divide_round_up (a: u128, b: u128) -> u128 {
   (1 + (a - 1) / b) * b
 }
 
-fn parse_input() -> Result<(u128, Vec<Option<u128>>), Box<dyn Error>> {
-  let mut lines = helpers::read_lines(INPUT_FILE)?;
-
-  let depart_time = lines.next().unwrap()?.parse::<u128>()?;
-  let bus_plan_string = lines.next().unwrap()?;
+fn parse_input(input: &InputSource) -> Result<(u128, Vec<Option<u128>>), Error> {
+  let mut lines = helpers::read_lines(input)?;
 
+  let depart_line = lines.next().ok_or_else(|| ParseError::missing_line(DAY, 1, "expected a departure time"))??;
+  let depart_time = SourceLine::new(DAY, 1, &depart_line).parse::<u128>(&depart_line)?;
+  let bus_plan_string = lines.next().ok_or_else(|| ParseError::missing_line(DAY, 2, "expected a bus plan"))??;
 
   let times: Vec<Option<u128>> = bus_plan_string.split(',').map(|t| t.parse::<u128>().ok()).collect();
   Ok((depart_time, times))
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn solves_example() {
+    let input = Day13::parse(&InputSource::File("input/test/day13".into())).unwrap();
+    assert_eq!(Day13::part_one(&input).unwrap(), Answer::Int(156));
+    assert_eq!(Day13::part_two(&input).unwrap(), Answer::Int(3417));
+  }
+}
@@ -1,40 +1,52 @@
-use crate::helpers;
-use std::error::Error;
+use crate::helpers::{self, InputSource};
+use crate::solution::{Answer, Solution};
+use crate::error::{Error, ParseError, SourceLine};
 use std::collections::HashMap;
 
-const INPUT_FILE: &str = "input/day14";
+pub const INPUT_FILE: &str = "input/day14";
+
+const DAY: u8 = 14;
 
 type Mask = Vec<char>;
 
 type Mem = (u64, u64);
 
-pub fn solve() -> Result<(), Box<dyn Error>> {
-  let lines = parse_input()?;
-  solve_part_one(lines);
-  let lines = parse_input()?;
-  solve_part_two(lines);
-  return Ok(());
+pub enum Instruction {
+  Mask(Mask),
+  Mem(Mem),
+}
+
+pub struct Day14;
+
+impl Solution for Day14 {
+  type Input = Vec<Instruction>;
+
+  fn parse(input: &InputSource) -> Result<Self::Input, Error> {
+    parse_input(input)
+  }
+
+  fn part_one(program: &Self::Input) -> Result<Answer, Error> {
+    Ok(solve_part_one(program))
+  }
+
+  fn part_two(program: &Self::Input) -> Result<Answer, Error> {
+    Ok(solve_part_two(program))
+  }
 }
 
-fn solve_part_one(lines: impl Iterator<Item=String> ) {
-  let mut current_mask: Option<Mask> = None;
+fn solve_part_one(program: &[Instruction]) -> Answer {
+  let mut current_mask: Option<&Mask> = None;
   let mut memory: HashMap<u64, u64> = HashMap::new();
 
-  for line in lines {
-    if line.starts_with("mask") {
-      current_mask = Some(parse_mask(line));
-    } else if line.starts_with("mem") {
-      if let Some(m) = &current_mask {
-        let (addr, val) = parse_mem(line);
+  for instruction in program {
+    match instruction {
+      Instruction::Mask(mask) => current_mask = Some(mask),
+      &Instruction::Mem((addr, val)) => if let Some(m) = current_mask {
         // apply mask
         let masked_val = apply_mask(m, val);
         // save
         memory.insert(addr, masked_val);
-      } else {
-        println!("Mem without any mask");
-      }
-    } else {
-      println!("Unkonwn line: {}", line);
+      },
     }
   }
 
@@ -43,30 +55,24 @@ fn solve_part_one(lines: impl Iterator<Item=String> ) {
     sum += memory.get(addr).unwrap();
   }
 
-  println!("Part 1: {}", sum);
+  sum.into()
 }
 
-fn solve_part_two(lines: impl Iterator<Item=String> ) {
-  let mut current_mask: Option<Mask> = None;
+fn solve_part_two(program: &[Instruction]) -> Answer {
+  let mut current_mask: Option<&Mask> = None;
   let mut memory: HashMap<u64, u64> = HashMap::new();
 
-  for line in lines {
-    if line.starts_with("mask") {
-      current_mask = Some(parse_mask(line));
-    } else if line.starts_with("mem") {
-      if let Some(m) = &current_mask {
-        let (addr, val) = parse_mem(line);
+  for instruction in program {
+    match instruction {
+      Instruction::Mask(mask) => current_mask = Some(mask),
+      &Instruction::Mem((addr, val)) => if let Some(m) = current_mask {
         // apply mask
         let masked_addrs = apply_mask_two(m, addr);
 
         for addr in masked_addrs {
           memory.insert(addr, val);
         }
-      } else {
-        println!("Mem without any mask");
-      }
-    } else {
-      println!("Unkonwn line: {}", line);
+      },
     }
   }
 
@@ -75,7 +81,7 @@ fn solve_part_two(lines: impl Iterator<Item=String> ) {
     sum += memory.get(addr).unwrap();
   }
 
-  println!("Part 2: {}", sum);
+  sum.into()
 }
 
 fn apply_mask(mask: &Mask, val: u64) -> u64 {
@@ -88,8 +94,6 @@ fn apply_mask(mask: &Mask, val: u64) -> u64 {
       '1' => 1,
       _ => (val >> (mask.len() - i - 1)) & 1
     };
-
-    println!("xx {}, {}, {}, {}", i, masked_val, val, mask.iter().collect::<String>());
   }
   
   masked_val
@@ -119,22 +123,73 @@ fn apply_mask_two(mask: &Mask, addr: u64) -> Vec<u64> {
   masked_values
 }
       
-fn parse_input() -> Result<impl Iterator<Item=String>, Box<dyn Error>> {
-  let lines = helpers::read_lines(INPUT_FILE)?.map(|l| l.unwrap());
+fn parse_input(input: &InputSource) -> Result<Vec<Instruction>, Error> {
+  let lines = helpers::read_lines(input)?;
+  let mut program = vec![];
+
+  // The solvers can then take every mem to come after a mask
+  let mut has_mask = false;
+  for (i, line) in lines.enumerate() {
+    let line = line?;
+    let source = SourceLine::new(DAY, i + 1, &line);
+    if line.starts_with("mask") {
+      program.push(Instruction::Mask(parse_mask(&source)?));
+      has_mask = true;
+    } else if line.starts_with("mem") {
+      if !has_mask {
+        return Err(source.error(&line, "mem before any mask").into());
+      }
+      program.push(Instruction::Mem(parse_mem(&source)?));
+    } else {
+      return Err(source.error(&line, "expected mask or mem").into());
+    }
+  }
+
+  Ok(program)
+}
 
-  Ok(lines)
+fn parse_mask(source: &SourceLine) -> Result<Mask, ParseError> {
+  let mask_string = source.expect(source.text.split('=').nth(1), "expected '='")?.trim();
+  match mask_string.chars().find(|c| !"01X".contains(*c)) {
+    Some(_) => Err(source.error(mask_string, "mask may only contain 0, 1 and X")),
+    None => Ok(mask_string.chars().collect()),
+  }
 }
 
-fn parse_mask(line: String) -> Mask {
-  let mask_string = line.split('=').skip(1).next().unwrap().trim();
+fn parse_mem(source: &SourceLine) -> Result<Mem, ParseError> {
+  let mut parts = source.text.split('=');
+  let mem_part = source.expect(parts.next(), "expected an address")?;
+  let val_part = source.expect(parts.next(), "expected '='")?;
+  let addr_part = mem_part.trim().trim_start_matches("mem[").trim_end_matches(']');
 
-  mask_string.chars().collect()
+  Ok((source.parse::<u64>(addr_part)?, source.parse::<u64>(val_part)?))
 }
 
-fn parse_mem(line: String) -> Mem {
-  let parts = line.split('=').collect::<Vec<_>>();
-  let mem_part = parts[0].chars().filter(|c| c.is_digit(10)).collect::<String>();
-  let val_part = parts[1].trim();
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn solves_example() {
+    let input = Day14::parse(&InputSource::File("input/test/day14".into())).unwrap();
+    assert_eq!(Day14::part_one(&input).unwrap(), Answer::Int(165));
+  }
+
+  #[test]
+  fn solves_example_2() {
+    let input = Day14::parse(&InputSource::File("input/test/day14_2".into())).unwrap();
+    assert_eq!(Day14::part_two(&input).unwrap(), Answer::Int(208));
+  }
 
-  (mem_part.parse::<u64>().unwrap(), val_part.parse::<u64>().unwrap())
-}
\ No newline at end of file
+  #[test]
+  fn reports_malformed_input() {
+    match Day14::parse(&InputSource::Text("mask = XX1\nmem[a] = 11\n".into())) {
+      Err(Error::Parse(e)) => assert_eq!((e.day, e.line, e.column), (14, 2, 5)),
+      _ => panic!("expected a parse error"),
+    }
+    match Day14::parse(&InputSource::Text("mem[8] = 11\nmask = XX1\n".into())) {
+      Err(Error::Parse(e)) => assert_eq!((e.line, e.message.as_str()), (1, "mem before any mask")),
+      _ => panic!("expected a parse error"),
+    }
+  }
+}
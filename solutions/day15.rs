@@ -1,17 +1,31 @@
-use crate::helpers;
-use std::error::Error;
+use crate::helpers::{self, InputSource};
+use crate::solution::{Answer, Solution};
+use crate::error::{Error, ParseError, SourceLine};
 use std::collections::HashMap;
 
-const INPUT_FILE: &str = "input/day15";
+pub const INPUT_FILE: &str = "input/day15";
 
-pub fn solve() -> Result<(), Box<dyn Error>> {
-  let start_numbers = parse_input()?;
-  solve_part_one(&start_numbers);
-  solve_part_two(&start_numbers);
-  return Ok(());
+const DAY: u8 = 15;
+
+pub struct Day15;
+
+impl Solution for Day15 {
+  type Input = Vec<u32>;
+
+  fn parse(input: &InputSource) -> Result<Self::Input, Error> {
+    parse_input(input)
+  }
+
+  fn part_one(start_numbers: &Self::Input) -> Result<Answer, Error> {
+    Ok(solve_part_one(start_numbers))
+  }
+
+  fn part_two(start_numbers: &Self::Input) -> Result<Answer, Error> {
+    Ok(solve_part_two(start_numbers))
+  }
 }
 
-fn solve_part_one(start_numbers: &Vec<u32>) {
+fn solve_part_one(start_numbers: &[u32]) -> Answer {
   let mut tracker = setup_start_map(start_numbers);
   let mut current_round = start_numbers.len();
   let mut prev_num = start_numbers[current_round - 1];
@@ -27,11 +41,10 @@ fn solve_part_one(start_numbers: &Vec<u32>) {
     prev_num = new_num;
 
   };
-  println!("Part 1: {}", prev_num);
-
+  prev_num.into()
 }
 
-fn solve_part_two(start_numbers: &Vec<u32>) {
+fn solve_part_two(start_numbers: &[u32]) -> Answer {
   let mut tracker = setup_start_map(start_numbers);
   let mut current_round = start_numbers.len();
   let mut prev_num = start_numbers[current_round - 1];
@@ -47,25 +60,36 @@ fn solve_part_two(start_numbers: &Vec<u32>) {
     prev_num = new_num;
 
   };
-  println!("Part 1: {}", prev_num);
-
+  prev_num.into()
 }
 
-fn setup_start_map(start_numbers: &Vec<u32>) -> HashMap<u32, u32> {
+fn setup_start_map(start_numbers: &[u32]) -> HashMap<u32, u32> {
   let mut start_map = HashMap::new();
-  for i in 0..start_numbers.len()-1 {
-    let num = start_numbers[i];
+  for (i, &num) in start_numbers[..start_numbers.len()-1].iter().enumerate() {
     start_map.insert(num, i as u32);
   }
 
   start_map
 }
       
-fn parse_input() -> Result<Vec<u32>, Box<dyn Error>> {
-  let mut lines = helpers::read_lines(INPUT_FILE)?;
-  let line = lines.next().unwrap();
+fn parse_input(input: &InputSource) -> Result<Vec<u32>, Error> {
+  let mut lines = helpers::read_lines(input)?;
+  let line = lines.next().ok_or_else(|| ParseError::missing_line(DAY, 1, "expected starting numbers"))??;
+  let source = SourceLine::new(DAY, 1, &line);
 
-  let numbers: Vec<u32> = line?.split(',').map(|n| n.parse::<u32>().unwrap()).collect();
+  let numbers = line.split(',').map(|n| source.parse::<u32>(n)).collect::<Result<Vec<_>, _>>()?;
 
   Ok(numbers)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn solves_example() {
+    let input = Day15::parse(&InputSource::File("input/test/day15".into())).unwrap();
+    assert_eq!(Day15::part_one(&input).unwrap(), Answer::Int(436));
+    assert_eq!(Day15::part_two(&input).unwrap(), Answer::Int(175594));
+  }
+}
@@ -1,22 +1,37 @@
-use crate::helpers;
-use std::error::Error;
+use crate::helpers::{self, InputSource};
+use crate::solution::{Answer, Solution};
+use crate::error::{Error, ParseError, SourceLine};
 use std::collections::HashMap;
 
-const INPUT_FILE: &str = "input/day16";
+pub const INPUT_FILE: &str = "input/day16";
+
+const DAY: u8 = 16;
 
 type Rule = (u32, u32);
 
+type Field = (String, Rule, Rule);
+
 type Ticket = Vec<u32>;
 
-pub fn solve() -> Result<(), Box<dyn Error>> {
-  let (ticket_rules, my_ticket, tickets) = parse_input()?;
-  solve_part_one(&ticket_rules, &tickets);
+pub struct Day16;
+
+impl Solution for Day16 {
+  type Input = (Vec<Field>, Ticket, Vec<Ticket>);
+
+  fn parse(input: &InputSource) -> Result<Self::Input, Error> {
+    parse_input(input)
+  }
+
+  fn part_one((ticket_rules, _, tickets): &Self::Input) -> Result<Answer, Error> {
+    Ok(solve_part_one(ticket_rules, tickets))
+  }
 
-  solve_part_two(&ticket_rules, &my_ticket, &tickets);
-  return Ok(());
+  fn part_two((ticket_rules, my_ticket, tickets): &Self::Input) -> Result<Answer, Error> {
+    Ok(solve_part_two(ticket_rules, my_ticket, tickets))
+  }
 }
 
-fn solve_part_one(ticket_rules: &Vec<(Rule, Rule)>, tickets: &Vec<Ticket>) {
+fn solve_part_one(ticket_rules: &[Field], tickets: &[Ticket]) -> Answer {
   let mut error_sum = 0;
   for ticket in tickets {
     for val in ticket {
@@ -26,13 +41,26 @@ fn solve_part_one(ticket_rules: &Vec<(Rule, Rule)>, tickets: &Vec<Ticket>) {
     }
   }
 
-  println!("Part 1: {}", error_sum)
+  error_sum.into()
+}
+
+fn solve_part_two(ticket_rules: &[Field], my_ticket: &Ticket, tickets: &[Ticket]) -> Answer {
+  let solution = find_field_positions(ticket_rules, my_ticket, tickets);
+
+  let mut departure_product: u64 = 1;
+  for (i, (name, _, _)) in ticket_rules.iter().enumerate() {
+    if name.starts_with("departure") {
+      let field = solution.get(&i).unwrap();
+      departure_product *= my_ticket[*field] as u64;
+    }
+  }
+  departure_product.into()
 }
 
-fn solve_part_two(ticket_rules: &Vec<(Rule, Rule)>, my_ticket: &Ticket, tickets: &Vec<Ticket>) {
+fn find_field_positions(ticket_rules: &[Field], my_ticket: &Ticket, tickets: &[Ticket]) -> HashMap<usize, usize> {
   let valid_tickets: Vec<&Ticket> = tickets.iter().filter(|&t| {
     for val in t {
-      if !check_if_valid(&ticket_rules, *val) {
+      if !check_if_valid(ticket_rules, *val) {
         return false
       }
     }
@@ -42,12 +70,12 @@ fn solve_part_two(ticket_rules: &Vec<(Rule, Rule)>, my_ticket: &Ticket, tickets:
   let mut possible_rules: HashMap<usize, Vec<usize>> = HashMap::new();
 
   for i in 0..ticket_rules.len() {
-    let (rule1, rule2) = ticket_rules[i];
+    let (_, rule1, rule2) = &ticket_rules[i];
     for j in 0..ticket_rules.len() {
       let mut rule_is_valid = true;
-      for k in 0..valid_tickets.len() {
-        let val = valid_tickets[k][j];
-        if !check_rule(&rule1, val) && !check_rule(&rule2, val) {
+      for ticket in &valid_tickets {
+        let val = ticket[j];
+        if !check_rule(rule1, val) && !check_rule(rule2, val) {
           rule_is_valid = false;
           break;
         }
@@ -57,9 +85,7 @@ fn solve_part_two(ticket_rules: &Vec<(Rule, Rule)>, my_ticket: &Ticket, tickets:
         let r = possible_rules.get_mut(&j);
         match r {
           None => { 
-            let mut v: Vec<usize> = Vec::new();
-            v.push(i);
-            possible_rules.insert(j, v); 
+            possible_rules.insert(j, vec![i]);
           },
           Some(rules) => rules.push(i),
         };
@@ -71,7 +97,7 @@ fn solve_part_two(ticket_rules: &Vec<(Rule, Rule)>, my_ticket: &Ticket, tickets:
 
   while solution.len() < ticket_rules.len() {
     for (&field, rules) in &possible_rules {
-      let unused_rules = rules.iter().filter(|r| solution.get(r).is_none()).collect::<Vec<_>>();
+      let unused_rules = rules.iter().filter(|r| !solution.contains_key(r)).collect::<Vec<_>>();
 
       if unused_rules.len() == 1 {
         solution.insert(*unused_rules[0], field);
@@ -79,17 +105,11 @@ fn solve_part_two(ticket_rules: &Vec<(Rule, Rule)>, my_ticket: &Ticket, tickets:
     }
   }
 
-  let mut departure_product: u64 = 1;
-  for i in 0..6 {
-    let field = solution.get(&i).unwrap();
-    departure_product *= my_ticket[*field] as u64;
-  }
-  println!("Test : {:?}", departure_product);
-
+  solution
 }
 
-fn check_if_valid(ticket_rules: &Vec<(Rule, Rule)>, val: u32) -> bool {
-  for (rule1, rule2) in ticket_rules {
+fn check_if_valid(ticket_rules: &[Field], val: u32) -> bool {
+  for (_, rule1, rule2) in ticket_rules {
     if check_rule(rule1, val) || check_rule(rule2, val) {
       return true;
     }
@@ -102,48 +122,83 @@ fn check_rule(rule: &Rule, val: u32) -> bool {
   val >= rule.0 && val <= rule.1
 }
       
-fn parse_input() -> Result<(Vec<(Rule, Rule)>, Ticket, Vec<Ticket>), Box<dyn Error>> {
-  let mut lines = helpers::read_lines(INPUT_FILE)?;
+fn parse_input(input: &InputSource) -> Result<(Vec<Field>, Ticket, Vec<Ticket>), Error> {
+  let lines = helpers::read_lines(input)?.collect::<Result<Vec<_>, _>>()?;
+  let mut sources = SourceLine::numbered(DAY, &lines);
 
-  let mut ticket_rules: Vec<(Rule, Rule)> = vec![];
-  loop {
-    let line = lines.next().unwrap()?;
-    if line.trim().len() == 0 {
+  let mut ticket_rules: Vec<Field> = vec![];
+  for source in &mut sources {
+    if source.text.trim().is_empty() {
       break;
     }
 
-    let rule_part = line.split(": ").skip(1).next().unwrap();
-    let mut rules = rule_part.split(" or ");
-    let rule1 = parse_rule(rules.next().unwrap())?;
-    let rule2 = parse_rule(rules.next().unwrap())?;
+    let mut parts = source.text.split(": ");
+    let name = source.expect(parts.next(), "expected a field name")?.to_string();
+    let mut rules = source.expect(parts.next(), "expected ': '")?.split(" or ");
+    let rule1 = parse_rule(&source, source.expect(rules.next(), "expected a range")?)?;
+    let rule2 = parse_rule(&source, source.expect(rules.next(), "expected ' or '")?)?;
 
-    ticket_rules.push((rule1, rule2));
+    ticket_rules.push((name, rule1, rule2));
   }
 
-  lines.next();
+  sources.next();
 
-  let my_ticket = parse_ticket(lines.next().unwrap()?.as_str())?;
+  let my_ticket = match sources.next() {
+    Some(source) => parse_ticket(&source)?,
+    None => return Err(ParseError::missing_line(DAY, lines.len() + 1, "expected your ticket").into()),
+  };
 
-  lines.next();
-  lines.next();
+  sources.next();
+  sources.next();
 
   let mut tickets = vec![];
-  for line in lines {
-    tickets.push(parse_ticket(line?.as_str())?);
+  for source in sources {
+    tickets.push(parse_ticket(&source)?);
   }
 
   Ok((ticket_rules, my_ticket, tickets))
 }
 
-fn parse_rule(rule_str: &str) -> Result<Rule, Box<dyn Error>> {
+fn parse_rule(source: &SourceLine, rule_str: &str) -> Result<Rule, ParseError> {
   let mut parts = rule_str.split('-');
-  let lower = parts.next().unwrap().parse::<u32>()?;
-  let upper = parts.next().unwrap().parse::<u32>()?;
+  let lower = source.parse::<u32>(source.expect(parts.next(), "expected a lower bound")?)?;
+  let upper = source.parse::<u32>(source.expect(parts.next(), "expected '-'")?)?;
   Ok((lower, upper))
 }
 
-fn parse_ticket(ticket_str: &str) -> Result<Ticket, Box<dyn Error>> {
-  let numbers: Vec<u32> = ticket_str.split(',').map(|n| n.parse::<u32>().unwrap()).collect();
+fn parse_ticket(source: &SourceLine) -> Result<Ticket, ParseError> {
+  source.text.split(',').map(|n| source.parse::<u32>(n)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
 
-  Ok(numbers)
-}
\ No newline at end of file
+  #[test]
+  fn solves_example() {
+    let input = Day16::parse(&InputSource::File("input/test/day16".into())).unwrap();
+    assert_eq!(Day16::part_one(&input).unwrap(), Answer::Int(71));
+  }
+
+  #[test]
+  fn finds_field_positions() {
+    let (ticket_rules, my_ticket, tickets) = Day16::parse(&InputSource::File("input/test/day16_2".into())).unwrap();
+    let positions = find_field_positions(&ticket_rules, &my_ticket, &tickets);
+    let named = |name: &str| {
+      let rule = ticket_rules.iter().position(|(field, _, _)| field == name).unwrap();
+      positions[&rule]
+    };
+    // The puzzle's second example: row is first, class second and seat third
+    assert_eq!((named("row"), named("class"), named("seat")), (0, 1, 2));
+  }
+
+  #[test]
+  fn multiplies_the_departure_fields() {
+    let text = std::fs::read_to_string("input/test/day16_2").unwrap()
+      .replace("class:", "departure class:")
+      .replace("row:", "departure row:");
+    let input = Day16::parse(&InputSource::Text(text)).unwrap();
+    // row is 11 and class 12 on our ticket; seat isn't a departure field
+    assert_eq!(Day16::part_two(&input).unwrap(), Answer::Int(11 * 12));
+  }
+}
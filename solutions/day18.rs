@@ -1,194 +1,194 @@
-use crate::helpers;
-use std::error::Error;
+use crate::helpers::{self, InputSource};
+use crate::solution::{Answer, Solution};
+use crate::error::{Error, ParseError, SourceLine};
+use crate::helpers::bigint::BigInt;
+use parser::{Expr, Operator};
+use precedence::PrecedenceTable;
 
-const INPUT_FILE: &str = "input/day18";
+pub mod parser;
+pub mod precedence;
+pub mod repl;
 
-pub fn solve() -> Result<(), Box<dyn Error>> {
-  let lines = parse_input()?;
-  solve_part_one(lines);
-  let lines = parse_input()?;
-  solve_part_two(lines);
-  return Ok(());
-}
+pub const INPUT_FILE: &str = "input/day18";
+
+const DAY: u8 = 18;
+
+pub struct Day18;
+
+impl Solution for Day18 {
+  type Input = Vec<String>;
+
+  fn parse(input: &InputSource) -> Result<Self::Input, Error> {
+    parse_input(input)
+  }
 
-fn solve_part_one(lines: impl Iterator<Item=String>) {
-  let sum = lines.fold(0, |sum, l| {
-    let mut chars = l.chars();
-    sum + evaluate_expression(&mut chars).unwrap()
-  });
+  // A line that overflows an i64 fails the part, pointing at that line
+  fn part_one(lines: &Self::Input) -> Result<Answer, Error> {
+    Ok(sum_lines::<i64>(lines, &PrecedenceTable::left_to_right())?.into())
+  }
+
+  fn part_two(lines: &Self::Input) -> Result<Answer, Error> {
+    Ok(sum_lines::<i64>(lines, &PrecedenceTable::addition_first())?.into())
+  }
+}
 
-  println!("Part 1: {}", sum);
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ArithmeticError {
+  Overflow,
+  DivisionByZero,
 }
 
-fn solve_part_two(lines: impl Iterator<Item=String>) {
-  let sum = lines.fold(0, |sum, l| {
-    let mut chars = l.chars();
-    sum + evaluate_expression_two(&mut chars).unwrap()
-  });
+// What the evaluator computes with: checked i64s, or BigInts that can't overflow
+pub trait Number: Sized {
+  fn from_i64(n: i64) -> Self;
+  fn apply(&self, op: Operator, rhs: &Self) -> Result<Self, ArithmeticError>;
 
-  println!("Part 2: {}", sum);
+  // Reads a literal's digits with the type's own arithmetic, so only a literal too long for
+  // the type overflows
+  fn from_digits(digits: &str) -> Result<Self, ArithmeticError> {
+    let ten = Self::from_i64(10);
+    digits.bytes().try_fold(Self::from_i64(0), |n, digit| {
+      n.apply(Operator::Mul, &ten)?.apply(Operator::Add, &Self::from_i64((digit - b'0') as i64))
+    })
+  }
 }
 
+impl Number for i64 {
+  fn from_i64(n: i64) -> i64 {
+    n
+  }
 
-fn evaluate_expression (chars: &mut impl Iterator<Item=char>) -> Option<u64> {
-  let mut result: Option<u64> = None;
-  let mut current_operator: Option<char> = None;
-  while let Some(c) = chars.next() {
-    match c {
-      c if c.is_digit(10) => {
-        let val = c.to_digit(10).unwrap() as u64;
-        match result {
-          None => result = Some(val),
-          Some(n) => {
-            result = match current_operator {
-              Some('+') => Some(n + val),
-              Some('*') => Some( n * val),
-              _ => {
-                println!("new digit without operator");
-                result
-              }
-            };
-            current_operator = None;
-          }
-        }
-      }
-      '+' => current_operator = Some('+'),
-      '*' => current_operator = Some('*'),
-      '(' => {
-        let val = evaluate_expression(chars).unwrap();
-        match result {
-          None => result = Some(val),
-          Some(n) => {
-            result = match current_operator {
-              Some('+') => Some(n + val),
-              Some('*') => Some(n * val),
-              _ => {
-                println!("new digit without operator");
-                result
-              }
-            };
-            current_operator = None;
-          }
-        }
-      },
-      ')' => return result,
-      _ => ()
+  fn apply(&self, op: Operator, rhs: &i64) -> Result<i64, ArithmeticError> {
+    if *rhs == 0 && (op == Operator::Div || op == Operator::Rem) {
+      return Err(ArithmeticError::DivisionByZero);
+    }
+    let result = match op {
+      Operator::Add => self.checked_add(*rhs),
+      Operator::Sub => self.checked_sub(*rhs),
+      Operator::Mul => self.checked_mul(*rhs),
+      Operator::Div => self.checked_div(*rhs),
+      Operator::Rem => self.checked_rem(*rhs),
     };
+    result.ok_or(ArithmeticError::Overflow)
   }
-
-  result
 }
 
-fn evaluate_expression_two (chars: &mut impl Iterator<Item=char>) -> Option<u64> {
-  let mut result: Option<u64> = None;
-  let mut current_operator: Option<char> = None;
-  let mut multi_part: Option<u64> = None;
-
-  while let Some(c) = chars.next() {
-    match c {
-      c if c.is_digit(10) => {
-        let val = c.to_digit(10).unwrap() as u64;
-        match result {
-          None => match current_operator {
-            Some('+') => match multi_part {
-              None => multi_part = Some(val),
-              Some(m) => multi_part = Some(m + val),
-            }
-            Some('*') => {
-              if let Some(m) = multi_part {
-                result = Some(m);
-              }
-              multi_part = Some(val);
-            }
-            _ => {
-              multi_part = Some(val);
-            }
-          }
-          Some(n) => {
-            match current_operator {
-              Some('+') => match multi_part {
-                None => multi_part = Some(val),
-                Some(m) => multi_part = Some(m + val),
-              }
-              Some('*') => {
-                if let Some(m) = multi_part {
-                  result = Some(n * m);
-                }
-                multi_part = Some(val);
-              }
-              _ => {
-                println!("new digit without operator")
-              }
-            };
-            current_operator = None;
-          }
-        }
-      }
-      '+' => current_operator = Some('+'),
-      '*' => current_operator = Some('*'),
-      '(' => {
-        let val = evaluate_expression_two(chars).unwrap();
-        match result {
-          None => match current_operator {
-            Some('+') => match multi_part {
-              None => multi_part = Some(val),
-              Some(m) => multi_part = Some(m + val),
-            }
-            Some('*') => {
-              if let Some(m) = multi_part {
-                result = Some(m);
-              }
-              multi_part = Some(val);
-            }
-            _ => {
-              multi_part = Some(val);
-            }
-          }
-          Some(n) => {
-            match current_operator {
-              Some('+') => match multi_part {
-                None => result = Some(n + val),
-                Some(m) => multi_part = Some(m + val),
-              }
-              Some('*') => {
-                if let Some(m) = multi_part {
-                  result = Some(n * m);
-                }
-                multi_part = Some(val);
-              }
-              _ => {
-                println!("new digit without operator")
-              }
-            };
-            current_operator = None;
-          }
-        }
-      },
-      ')' => {
-        if let Some(n) = multi_part {
-          match result {
-            None => result = Some(n),
-            Some(m) => result = Some(n * m),
-          }    
-        }
-        return result
-      }
-      _ => ()
-    };
+impl Number for BigInt {
+  fn from_i64(n: i64) -> BigInt {
+    BigInt::from(n)
   }
 
-  if let Some(n) = multi_part {
-    match result {
-      None => result = Some(n),
-      Some(m) => result = Some(n * m),
+  fn apply(&self, op: Operator, rhs: &BigInt) -> Result<BigInt, ArithmeticError> {
+    match op {
+      Operator::Add => Ok(self + rhs),
+      Operator::Sub => Ok(self - rhs),
+      Operator::Mul => Ok(self * rhs),
+      Operator::Div => self.div_rem(rhs).map(|(quotient, _)| quotient).ok_or(ArithmeticError::DivisionByZero),
+      Operator::Rem => self.div_rem(rhs).map(|(_, remainder)| remainder).ok_or(ArithmeticError::DivisionByZero),
     }
   }
+}
+
+// The lines were checked when parsing the input, but a table without some operator rejects
+// expressions that use it. Overflow and division by zero are reported on the line they happen.
+pub fn sum_lines<T: Number>(lines: &[String], table: &PrecedenceTable) -> Result<T, ParseError> {
+  let mut sum = T::from_i64(0);
+  for line in SourceLine::numbered(DAY, lines) {
+    let value = evaluate_line(&line, table)?;
+    sum = sum.apply(Operator::Add, &value)
+      .map_err(|_| line.error(line.text, "the sum overflows a 64-bit integer here"))?;
+  }
+
+  Ok(sum)
+}
+
+pub fn evaluate_line<T: Number>(line: &SourceLine, table: &PrecedenceTable) -> Result<T, ParseError> {
+  evaluate_parsed(line, &parser::parse(line, table)?)
+}
+
+// Evaluates an expression parsed from `line`, reporting arithmetic errors on that line
+pub fn evaluate_parsed<T: Number>(line: &SourceLine, expr: &Expr) -> Result<T, ParseError> {
+  evaluate(expr).map_err(|e| match e {
+    ArithmeticError::Overflow => line.error(line.text, "overflows a 64-bit integer"),
+    ArithmeticError::DivisionByZero => line.error(line.text, "division by zero"),
+  })
+}
 
-  result
+pub fn evaluate<T: Number>(expr: &Expr) -> Result<T, ArithmeticError> {
+  match expr {
+    Expr::Number(digits) => T::from_digits(digits),
+    Expr::Binary(op, lhs, rhs) => evaluate::<T>(lhs)?.apply(*op, &evaluate(rhs)?),
+  }
 }
-      
-fn parse_input() -> Result<impl Iterator<Item=String>, Box<dyn Error>> {
-  let lines = helpers::read_lines(INPUT_FILE)?.map(|l| l.unwrap());
+
+// Syntax doesn't depend on precedence, so one parse per line is enough to report errors
+fn parse_input(input: &InputSource) -> Result<Vec<String>, Error> {
+  let lines = helpers::read_lines(input)?.collect::<Result<Vec<_>, _>>()?;
+
+  for line in SourceLine::numbered(DAY, &lines) {
+    parser::parse(&line, &PrecedenceTable::left_to_right())?;
+  }
 
   Ok(lines)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn solves_example() {
+    let input = Day18::parse(&InputSource::File("input/test/day18".into())).unwrap();
+    assert_eq!(Day18::part_one(&input).unwrap(), Answer::Int(13632));
+    assert_eq!(Day18::part_two(&input).unwrap(), Answer::Int(23340));
+  }
+
+  #[test]
+  fn solves_example_2() {
+    let input = Day18::parse(&InputSource::File("input/test/day18_2".into())).unwrap();
+    assert_eq!(Day18::part_one(&input).unwrap(), Answer::Int(44));
+    assert_eq!(Day18::part_two(&input).unwrap(), Answer::Int(60));
+  }
+
+  #[test]
+  fn evaluates_with_any_table() {
+    let lines = vec!["2 * 3 + 4 * 5".to_string(), "17 % 5 - 8 / 4".to_string()];
+    assert_eq!(sum_lines::<i64>(&lines, &PrecedenceTable::normal()), Ok(26));
+    assert_eq!(sum_lines::<i64>(&lines, &PrecedenceTable::left_to_right()), Ok(49));
+    assert_eq!(sum_lines::<i64>(&lines, &PrecedenceTable::addition_first()), Ok(70));
+    assert!(sum_lines::<i64>(&lines, &PrecedenceTable::parse("+ < *").unwrap()).is_err());
+  }
+
+  #[test]
+  fn reports_the_line_that_overflows() {
+    let lines = vec!["1 + 2".to_string(), "(1000000 * 1000000) * (1000000 * 1000000)".to_string(), "1 / (2 - 2)".to_string()];
+    let error = sum_lines::<i64>(&lines, &PrecedenceTable::normal()).unwrap_err();
+    assert_eq!((error.line, error.message.as_str()), (2, "overflows a 64-bit integer"));
+    let error = sum_lines::<BigInt>(&lines, &PrecedenceTable::normal()).unwrap_err();
+    assert_eq!((error.line, error.message.as_str()), (3, "division by zero"));
+
+    let big = sum_lines::<BigInt>(&lines[..2], &PrecedenceTable::normal()).unwrap();
+    assert_eq!(big.to_string(), "1000000000000000000000003");
+    match Day18::part_one(&lines[..2].to_vec()) {
+      Err(Error::Parse(e)) => assert_eq!((e.line, e.message.as_str()), (2, "overflows a 64-bit integer")),
+      _ => panic!("expected the overflow to be reported"),
+    }
+  }
+
+  #[test]
+  fn reads_literals_too_long_for_an_i64_only_as_big_integers() {
+    let lines = vec!["2 * 3".to_string(), "99999999999999999999 + 1".to_string()];
+    let error = sum_lines::<i64>(&lines, &PrecedenceTable::normal()).unwrap_err();
+    assert_eq!((error.line, error.message.as_str()), (2, "overflows a 64-bit integer"));
+    let big = sum_lines::<BigInt>(&lines, &PrecedenceTable::normal()).unwrap();
+    assert_eq!(big.to_string(), "100000000000000000006");
+  }
+
+  #[test]
+  fn reports_malformed_input() {
+    match Day18::parse(&InputSource::Text("1 + 2\n(3 * 4\n".into())) {
+      Err(Error::Parse(e)) => assert_eq!((e.day, e.line, e.column), (18, 2, 1)),
+      _ => panic!("expected a parse error"),
+    }
+  }
+}
@@ -0,0 +1,205 @@
+use super::precedence::{Associativity, PrecedenceTable};
+use crate::error::{ParseError, SourceLine};
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Operator {
+  Add,
+  Sub,
+  Mul,
+  Div,
+  Rem,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TokenKind {
+  Number,
+  Operator(Operator),
+  Open,
+  Close,
+}
+
+// A token and the text it was read from, kept to point errors at it
+pub type Token<'a> = (TokenKind, &'a str);
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr {
+  // The literal's digits, left for the evaluator to read into whatever type it computes with
+  Number(String),
+  Binary(Operator, Box<Expr>, Box<Expr>),
+}
+
+// Fully parenthesised, showing how the expression was grouped
+impl fmt::Display for Expr {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Expr::Number(n) => write!(f, "{}", n),
+      Expr::Binary(op, lhs, rhs) => write!(f, "({} {} {})", lhs, op.symbol(), rhs),
+    }
+  }
+}
+
+impl Operator {
+  pub fn from_symbol(c: char) -> Option<Operator> {
+    match c {
+      '+' => Some(Operator::Add),
+      '-' => Some(Operator::Sub),
+      '*' => Some(Operator::Mul),
+      '/' => Some(Operator::Div),
+      '%' => Some(Operator::Rem),
+      _ => None,
+    }
+  }
+
+  pub fn symbol(&self) -> char {
+    match self {
+      Operator::Add => '+',
+      Operator::Sub => '-',
+      Operator::Mul => '*',
+      Operator::Div => '/',
+      Operator::Rem => '%',
+    }
+  }
+}
+
+pub fn tokenize<'a>(line: &SourceLine<'a>) -> Result<Vec<Token<'a>>, ParseError> {
+  let text = line.text;
+  let mut tokens = vec![];
+  let mut chars = text.char_indices().peekable();
+
+  while let Some((start, c)) = chars.next() {
+    let kind = match c {
+      c if c.is_whitespace() => continue,
+      '0'..='9' => {
+        let mut end = start + 1;
+        while let Some(&(i, '0'..='9')) = chars.peek() {
+          end = i + 1;
+          chars.next();
+        }
+        tokens.push((TokenKind::Number, &text[start..end]));
+        continue;
+      },
+      '(' => TokenKind::Open,
+      ')' => TokenKind::Close,
+      _ => match Operator::from_symbol(c) {
+        Some(op) => TokenKind::Operator(op),
+        None => return Err(line.error(&text[start..], &format!("unexpected '{}'", c))),
+      },
+    };
+    tokens.push((kind, &text[start..start + c.len_utf8()]));
+  }
+
+  Ok(tokens)
+}
+
+pub fn parse(line: &SourceLine, table: &PrecedenceTable) -> Result<Expr, ParseError> {
+  let tokens = tokenize(line)?;
+  let mut parser = Parser { line, tokens: &tokens, pos: 0, table };
+  let expr = parser.expression(0)?;
+
+  match parser.next() {
+    None => Ok(expr),
+    Some((TokenKind::Close, text)) => Err(line.error(text, "unmatched ')'")),
+    Some((_, text)) => Err(line.error(text, "expected an operator")),
+  }
+}
+
+struct Parser<'a, 'b> {
+  line: &'b SourceLine<'a>,
+  tokens: &'b [Token<'a>],
+  pos: usize,
+  table: &'b PrecedenceTable,
+}
+
+impl<'a, 'b> Parser<'a, 'b> {
+  fn peek(&self) -> Option<Token<'a>> {
+    self.tokens.get(self.pos).copied()
+  }
+
+  fn next(&mut self) -> Option<Token<'a>> {
+    let token = self.peek();
+    self.pos += 1;
+    token
+  }
+
+  // Precedence climbing: keep taking operators that bind at least as tightly as `min`. A left
+  // associative operator parses its right-hand side with a higher minimum, so the next operator
+  // of the same precedence ends it and groups to the left instead.
+  fn expression(&mut self, min: u8) -> Result<Expr, ParseError> {
+    let mut lhs = self.operand()?;
+
+    while let Some((TokenKind::Operator(op), text)) = self.peek() {
+      let (precedence, associativity) = self.table.binding(op)
+        .ok_or_else(|| self.line.error(text, &format!("'{}' is not in the precedence table", op.symbol())))?;
+      if precedence < min {
+        break;
+      }
+      self.pos += 1;
+      let next_min = match associativity {
+        Associativity::Left => precedence + 1,
+        Associativity::Right => precedence,
+      };
+      let rhs = self.expression(next_min)?;
+      lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+    }
+
+    Ok(lhs)
+  }
+
+  fn operand(&mut self) -> Result<Expr, ParseError> {
+    match self.next() {
+      Some((TokenKind::Number, digits)) => Ok(Expr::Number(digits.to_string())),
+      Some((TokenKind::Open, open)) => {
+        let expr = self.expression(0)?;
+        match self.next() {
+          Some((TokenKind::Close, _)) => Ok(expr),
+          Some((_, text)) => Err(self.line.error(text, "expected an operator or ')'")),
+          None => Err(self.line.error(open, "unclosed '('")),
+        }
+      },
+      Some((_, text)) => Err(self.line.error(text, "expected a number or '('")),
+      None => Err(self.line.error(&self.line.text[self.line.text.len()..], "expected a number or '('")),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn parse_text(text: &str) -> Result<Expr, ParseError> {
+    parse(&SourceLine::new(18, 1, text), &PrecedenceTable::left_to_right())
+  }
+
+  #[test]
+  fn parses_multi_digit_literals_left_to_right() {
+    let num = |n: u32| Box::new(Expr::Number(n.to_string()));
+    let expected = Expr::Binary(Operator::Mul, Box::new(Expr::Binary(Operator::Add, num(12), num(3))), num(405));
+    assert_eq!(parse_text("12+3 *   405").unwrap(), expected);
+    assert_eq!(parse_text("((12 + 3)) * 405").unwrap(), expected);
+    assert_eq!(expected.to_string(), "((12 + 3) * 405)");
+  }
+
+  #[test]
+  fn groups_by_associativity() {
+    let num = |n: u32| Box::new(Expr::Number(n.to_string()));
+    let line = SourceLine::new(18, 1, "8 - 4 - 2");
+    let left = parse(&line, &PrecedenceTable::parse("-").unwrap()).unwrap();
+    assert_eq!(left, Expr::Binary(Operator::Sub, Box::new(Expr::Binary(Operator::Sub, num(8), num(4))), num(2)));
+    let right = parse(&line, &PrecedenceTable::parse("- right").unwrap()).unwrap();
+    assert_eq!(right, Expr::Binary(Operator::Sub, num(8), Box::new(Expr::Binary(Operator::Sub, num(4), num(2)))));
+  }
+
+  #[test]
+  fn reports_syntax_errors_by_column() {
+    let column = |text| parse_text(text).map(|_| ()).map_err(|e| (e.column, e.message));
+    assert_eq!(column("1 + + 2"), Err((5, "expected a number or '('".to_string())));
+    assert_eq!(column("1 + (2 * 3"), Err((5, "unclosed '('".to_string())));
+    assert_eq!(column("1 + 2)"), Err((6, "unmatched ')'".to_string())));
+    assert_eq!(column("1 2"), Err((3, "expected an operator".to_string())));
+    assert_eq!(column("2 ^ 1"), Err((3, "unexpected '^'".to_string())));
+    let error = parse(&SourceLine::new(18, 1, "2 * 3 - 1"), &PrecedenceTable::parse("+ < *").unwrap()).unwrap_err();
+    assert_eq!((error.column, error.message.as_str()), (7, "'-' is not in the precedence table"));
+    assert_eq!(column("3 *"), Err((4, "expected a number or '('".to_string())));
+  }
+}
@@ -0,0 +1,135 @@
+use super::parser::Operator;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Associativity {
+  Left,
+  Right,
+}
+
+// How tightly an operator binds (higher binds tighter) and how repeated uses of it group
+pub type Binding = (u8, Associativity);
+
+// The operators an expression may use and how they bind; anything missing is a syntax error
+#[derive(Clone, Debug, PartialEq)]
+pub struct PrecedenceTable {
+  bindings: Vec<(Operator, Binding)>,
+}
+
+pub const PRESETS: [&str; 3] = ["normal", "left-to-right", "addition-first"];
+
+impl PrecedenceTable {
+  // Levels go from the loosest to the tightest binding
+  pub fn new(levels: &[(&[Operator], Associativity)]) -> PrecedenceTable {
+    let bindings = levels.iter().enumerate()
+      .flat_map(|(level, (operators, associativity))| {
+        operators.iter().map(move |&op| (op, (level as u8 + 1, *associativity)))
+      })
+      .collect();
+    PrecedenceTable { bindings }
+  }
+
+  // Usual arithmetic: * / % before + -
+  pub fn normal() -> PrecedenceTable {
+    use Operator::*;
+    PrecedenceTable::new(&[(&[Add, Sub], Associativity::Left), (&[Mul, Div, Rem], Associativity::Left)])
+  }
+
+  // Part one: every operator binds equally, so evaluation goes left to right
+  pub fn left_to_right() -> PrecedenceTable {
+    use Operator::*;
+    PrecedenceTable::new(&[(&[Add, Sub, Mul, Div, Rem], Associativity::Left)])
+  }
+
+  // Part two: + - before * / %
+  pub fn addition_first() -> PrecedenceTable {
+    use Operator::*;
+    PrecedenceTable::new(&[(&[Mul, Div, Rem], Associativity::Left), (&[Add, Sub], Associativity::Left)])
+  }
+
+  pub fn from_name(name: &str) -> Option<PrecedenceTable> {
+    match name {
+      "normal" => Some(PrecedenceTable::normal()),
+      "left-to-right" => Some(PrecedenceTable::left_to_right()),
+      "addition-first" => Some(PrecedenceTable::addition_first()),
+      _ => None,
+    }
+  }
+
+  // A preset name, or levels separated by '<' from the loosest to the tightest, each listing its
+  // operators and ending in "right" if they group to the right, e.g. "+ - < * / % right"
+  pub fn parse(spec: &str) -> Result<PrecedenceTable, String> {
+    if let Some(table) = PrecedenceTable::from_name(spec) {
+      return Ok(table);
+    }
+
+    let mut levels = vec![];
+    let mut seen = vec![];
+    for level in spec.split('<') {
+      let (operators, associativity) = match level.trim().strip_suffix("right") {
+        Some(operators) => (operators, Associativity::Right),
+        None => (level, Associativity::Left),
+      };
+      let operators = operators.chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| Operator::from_symbol(c).ok_or_else(|| format!("'{}' is not an operator", c)))
+        .collect::<Result<Vec<_>, _>>()?;
+      if operators.is_empty() {
+        return Err(format!("empty precedence level in '{}'", spec));
+      }
+      if let Some(op) = operators.iter().find(|&op| seen.contains(op)) {
+        return Err(format!("'{}' appears more than once", op.symbol()));
+      }
+      seen.extend(&operators);
+      levels.push((operators, associativity));
+    }
+
+    let levels = levels.iter().map(|(operators, associativity)| (operators.as_slice(), *associativity)).collect::<Vec<_>>();
+    Ok(PrecedenceTable::new(&levels))
+  }
+
+  pub fn binding(&self, op: Operator) -> Option<Binding> {
+    self.bindings.iter().find(|(other, _)| *other == op).map(|&(_, binding)| binding)
+  }
+}
+
+// The table in the syntax PrecedenceTable::parse reads
+impl fmt::Display for PrecedenceTable {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let mut levels: Vec<(u8, Associativity, Vec<String>)> = vec![];
+    for &(op, (level, associativity)) in &self.bindings {
+      match levels.iter_mut().find(|(l, _, _)| *l == level) {
+        Some((_, _, symbols)) => symbols.push(op.symbol().to_string()),
+        None => levels.push((level, associativity, vec![op.symbol().to_string()])),
+      }
+    }
+    levels.sort_by_key(|&(level, _, _)| level);
+
+    let levels = levels.into_iter().map(|(_, associativity, symbols)| match associativity {
+      Associativity::Left => symbols.join(" "),
+      Associativity::Right => format!("{} right", symbols.join(" ")),
+    }).collect::<Vec<_>>();
+    write!(f, "{}", levels.join(" < "))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parses_and_displays_tables() {
+    assert_eq!(PrecedenceTable::normal().to_string(), "+ - < * / %");
+    assert_eq!(PrecedenceTable::parse("addition-first"), Ok(PrecedenceTable::addition_first()));
+    assert_eq!(PrecedenceTable::parse("*/% < +-"), Ok(PrecedenceTable::addition_first()));
+
+    let table = PrecedenceTable::parse("+ < * right").unwrap();
+    assert_eq!(table.binding(Operator::Mul), Some((2, Associativity::Right)));
+    assert_eq!(table.binding(Operator::Sub), None);
+    assert_eq!(PrecedenceTable::parse(&table.to_string()), Ok(table));
+
+    assert!(PrecedenceTable::parse("+ < ^").is_err());
+    assert!(PrecedenceTable::parse("+ < < *").is_err());
+    assert!(PrecedenceTable::parse("+ * < +").is_err());
+  }
+}
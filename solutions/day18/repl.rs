@@ -0,0 +1,66 @@
+use super::parser;
+use super::precedence::PrecedenceTable;
+use super::{evaluate_parsed, DAY};
+use crate::error::SourceLine;
+use crate::helpers::bigint::BigInt;
+use std::io::{self, BufRead, Write};
+
+// Evaluates every line of input under both puzzle precedences, optionally showing how each
+// was grouped
+pub fn repl(input: impl BufRead, mut output: impl Write, show_parse: bool) -> io::Result<()> {
+  let tables = [
+    ("left-to-right", PrecedenceTable::left_to_right()),
+    ("addition-first", PrecedenceTable::addition_first()),
+  ];
+
+  prompt(&mut output)?;
+  for (i, line) in input.lines().enumerate() {
+    let line = line?;
+    let source = SourceLine::new(DAY, i + 1, &line);
+    if line.trim().is_empty() {
+      prompt(&mut output)?;
+      continue;
+    }
+
+    for (name, table) in &tables {
+      // Every operator is in both tables, so a syntax error is the same under either
+      let expr = match parser::parse(&source, table) {
+        Ok(expr) => expr,
+        Err(e) => {
+          writeln!(output, "{}", e)?;
+          break;
+        },
+      };
+      match evaluate_parsed::<BigInt>(&source, &expr) {
+        Ok(value) if show_parse => writeln!(output, "{:<15} {} = {}", name, expr, value)?,
+        Ok(value) => writeln!(output, "{:<15} {}", name, value)?,
+        Err(e) => writeln!(output, "{:<15} {}", name, e.message)?,
+      }
+    }
+    prompt(&mut output)?;
+  }
+
+  writeln!(output)?;
+  Ok(())
+}
+
+fn prompt(output: &mut impl Write) -> io::Result<()> {
+  write!(output, "(day18) ")?;
+  output.flush()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn evaluates_each_line_both_ways() {
+    let mut output = vec![];
+    repl("2 * 3 + 4\n\n2 *\n8 / (2 * 2 - 4)\n".as_bytes(), &mut output, true).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains("left-to-right   ((2 * 3) + 4) = 10\n"));
+    assert!(output.contains("addition-first  (2 * (3 + 4)) = 14\n"));
+    assert!(output.contains("left-to-right   division by zero\naddition-first  (8 / (2 * (2 - 4))) = -2\n"));
+    assert!(output.contains("line 3, column 4: expected a number or '('"));
+  }
+}
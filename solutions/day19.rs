@@ -1,113 +1,179 @@
-use crate::helpers;
-use std::error::Error;
+use crate::helpers::{self, InputSource};
+use crate::solution::{Answer, Solution};
+use crate::error::{Error, ParseError, SourceLine};
+use dfa::Dfa;
+use grammar::Grammar;
 use std::collections::HashMap;
 
-const INPUT_FILE: &str = "input/day19";
+pub mod derivation;
+pub mod dfa;
+pub mod grammar;
 
-enum Rule {
+pub const INPUT_FILE: &str = "input/day19";
+
+const DAY: u8 = 19;
+
+// Part two swaps these in for rules 8 and 11, making them recursive
+const PART_TWO_RULES: [&str; 2] = ["8: 42 | 42 8", "11: 42 31 | 42 11 31"];
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Rule {
   Literal(String),
-  Sequence(Vec<usize>),
-  Sub(Vec<usize>, Vec<usize>),
+  // Any number of alternatives, each a sequence of rule numbers
+  Alternatives(Vec<Vec<usize>>),
 }
 
-type RuleMap = HashMap<usize, Rule>;
+pub type RuleMap = HashMap<usize, Rule>;
 
-pub fn solve() -> Result<(), Box<dyn Error>> {
-  let mut lines = parse_input()?;
-  let mut rules = parse_rules(&mut lines)?;
-  solve_part_one(lines, &rules);
+// Rules without recursion describe a regular language and run as a DFA; recursive ones, like
+// rules 8 and 11 in part two, need the general parser, as do rules whose DFA would be too big
+pub enum Matcher {
+  Dfa(Dfa),
+  Earley(Grammar),
+}
 
-  rules.insert(8, Rule::Sub(vec![42], vec![42, 8]));
-  rules.insert(11, Rule::Sub(vec![42, 31], vec![42, 11, 31]));
-  let lines = parse_input()?;
-  solve_part_one(lines, &rules);
+pub struct Day19;
 
-  return Ok(());
-}
+impl Solution for Day19 {
+  type Input = (RuleMap, Vec<String>);
 
-fn solve_part_one(lines: impl Iterator<Item=String>, rules: &RuleMap) {
-  let mut matches = 0;
-  for line in lines {
-    // check if line fits
-    let r = test_rule(rules, line.as_str(), 0);
-    let line_len = line.len();
-    if r.contains(&line_len) {
-      matches += 1;
-    }
+  fn parse(input: &InputSource) -> Result<Self::Input, Error> {
+    parse_input(input)
+  }
+
+  fn part_one((rules, messages): &Self::Input) -> Result<Answer, Error> {
+    Ok(solve_part_one(messages, rules))
   }
 
-  println!("Part 1: {}", matches);
+  fn part_two((rules, messages): &Self::Input) -> Result<Answer, Error> {
+    Ok(solve_part_two(messages, rules))
+  }
 }
 
-fn test_rule(rule_map: &RuleMap, txt: &str, rule_num: usize) -> Vec<usize> {
-  let rule = rule_map.get(&rule_num).unwrap();
-
-  match rule {
-    Rule::Literal(s) => {
-      if txt.starts_with(s) {
-        vec![s.len()]
-      } else {
-        vec![]
-      }
-    },
-    Rule::Sequence(ss) => test_sequence(rule_map, txt, ss),
-    Rule::Sub(ss1, ss2) => {
-      let mut res1 = test_sequence(rule_map, txt, ss1);
-      let mut res2 = test_sequence(rule_map, txt, ss2);
-      res1.append(&mut res2);
-      res1
+fn solve_part_one(messages: &[String], rules: &RuleMap) -> Answer {
+  count_matches(messages, &Matcher::new(rules)).into()
+}
+
+fn solve_part_two(messages: &[String], rules: &RuleMap) -> Answer {
+  part_two_rules(rules).map(|rules| count_matches(messages, &Matcher::new(&rules))).ok().into()
+}
+
+pub fn part_two_rules(rules: &RuleMap) -> Result<RuleMap, ParseError> {
+  let lines = PART_TWO_RULES.iter().map(|line| line.to_string()).collect::<Vec<_>>();
+  let mut rules = rules.clone();
+  rules.extend(parse_rules(&mut SourceLine::numbered(DAY, &lines))?);
+  Ok(rules)
+}
+
+impl Matcher {
+  pub fn new(rules: &RuleMap) -> Matcher {
+    let grammar = Grammar::new(rules);
+    match Dfa::compile(&grammar, 0) {
+      Some(dfa) => Matcher::Dfa(dfa),
+      None => Matcher::Earley(grammar),
+    }
+  }
+
+  pub fn matches(&self, message: &str) -> bool {
+    match self {
+      Matcher::Dfa(dfa) => dfa.matches(message),
+      Matcher::Earley(grammar) => grammar.matches(0, message),
     }
   }
 }
 
-fn test_sequence(rule_map: &RuleMap, txt: &str, rule_seq: &Vec<usize>) -> Vec<usize> {
-  rule_seq.iter().fold(vec![0], |res, &r| {
-    let mut new_res = vec![];
-    for n in res {
-      let test_res = test_rule(rule_map, &txt[n..], r);
-      for m in test_res {
-        new_res.push(n + m);
-      }
-    };
-    new_res
-  })
+pub fn count_matches(messages: &[String], matcher: &Matcher) -> usize {
+  messages.iter().filter(|message| matcher.matches(message)).count()
 }
-      
-fn parse_input() -> Result<impl Iterator<Item=String>, Box<dyn Error>> {
-  let lines = helpers::read_lines(INPUT_FILE)?.map(|l| l.unwrap());
 
-  Ok(lines)
+fn parse_input(input: &InputSource) -> Result<(RuleMap, Vec<String>), Error> {
+  let lines = helpers::read_lines(input)?.collect::<Result<Vec<_>, _>>()?;
+  let mut sources = SourceLine::numbered(DAY, &lines);
+  let rules = parse_rules(&mut sources)?;
+
+  Ok((rules, sources.map(|source| source.text.to_string()).collect()))
 }
 
-fn parse_rules(lines: &mut impl Iterator<Item=String>) -> Result<RuleMap, Box<dyn Error>> {
+pub fn parse_rules<'a>(sources: &mut impl Iterator<Item=SourceLine<'a>>) -> Result<RuleMap, ParseError> {
   let mut rule_map: RuleMap = HashMap::new();
 
-  while let Some(l) = lines.next() {
-    if l.trim().len() == 0 {
+  for source in sources.by_ref() {
+    if source.text.trim().is_empty() {
       break;
     }
-    let mut parts = l.split(": ");
-    let rule_num = parts.next().unwrap().parse::<usize>()?;
-    let parts = parts.next().unwrap().split(" | ").collect::<Vec<_>>();
-
-    if parts.len() > 1 {
-      let rule = Rule::Sub(parse_sequence(parts[0]), parse_sequence(parts[1]));
-      rule_map.insert(rule_num, rule);
-    } else if parts[0].contains('"') {
-      let rule = Rule::Literal(parts[0].chars().filter(|&c| c != '"').collect());
-      rule_map.insert(rule_num, rule);
+    let mut parts = source.text.split(": ");
+    let rule_num = source.parse::<usize>(source.expect(parts.next(), "expected a rule number")?)?;
+    let body = source.expect(parts.next(), "expected ': '")?;
+
+    let rule = if body.contains('"') {
+      Rule::Literal(body.chars().filter(|&c| c != '"').collect())
     } else {
-      let rule = Rule::Sequence(parse_sequence(parts[0]));
-      rule_map.insert(rule_num, rule);
-    }
+      Rule::Alternatives(body.split('|').map(|part| parse_sequence(&source, part)).collect::<Result<_, _>>()?)
+    };
+    rule_map.insert(rule_num, rule);
   }
 
   Ok(rule_map)
 }
 
-fn parse_sequence(part: &str) -> Vec<usize> {
+fn parse_sequence(source: &SourceLine, part: &str) -> Result<Vec<usize>, ParseError> {
   part
     .split_whitespace()
-    .map(|s| s.parse::<usize>().unwrap())
+    .map(|s| source.parse::<usize>(s))
     .collect()
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn solves_example() {
+    let input = Day19::parse(&InputSource::File("input/test/day19".into())).unwrap();
+    assert_eq!(Day19::part_one(&input).unwrap(), Answer::Int(2));
+    assert_eq!(Day19::part_two(&input).unwrap(), Answer::Int(2));
+  }
+
+  #[test]
+  fn falls_back_to_the_parser_for_recursive_rules() {
+    let (rules, _) = Day19::parse(&InputSource::File("input/test/day19".into())).unwrap();
+    assert!(matches!(Matcher::new(&rules), Matcher::Dfa(_)));
+    let mut recursive = rules.clone();
+    recursive.insert(2, Rule::Alternatives(vec![vec![4, 4], vec![5, 2, 5]]));
+    let matcher = Matcher::new(&recursive);
+    assert!(matches!(matcher, Matcher::Earley(_)));
+    assert!(matcher.matches("abaababb"));
+  }
+
+  #[test]
+  fn falls_back_to_the_parser_for_deeply_shared_rules() {
+    // Each rule uses the next one twice, so rule 0 matches 2^26 a's and nothing else
+    let mut text: String = (0..26).map(|k| format!("{}: {} {}\n", k, k + 1, k + 1)).collect();
+    text.push_str("26: \"a\"\n");
+    let (rules, _) = Day19::parse(&InputSource::Text(text)).unwrap();
+    assert!(Dfa::compile(&Grammar::new(&rules), 0).is_none());
+    let matcher = Matcher::new(&rules);
+    assert!(matches!(matcher, Matcher::Earley(_)));
+    assert!(!matcher.matches("aaaa"));
+
+    // A few levels still fit in a DFA
+    let (shallow, _) = Day19::parse(&InputSource::Text("0: 1 1\n1: 2 2\n2: \"a\"\n".into())).unwrap();
+    let matcher = Matcher::new(&shallow);
+    assert!(matches!(matcher, Matcher::Dfa(_)));
+    assert!(matcher.matches("aaaa") && !matcher.matches("aaa"));
+  }
+
+  #[test]
+  fn parses_any_number_of_alternatives() {
+    let (rules, _) = Day19::parse(&InputSource::Text("0: 1 | 2 1 | 2 2 2\n1: \"a\"\n2: \"b\"\n".into())).unwrap();
+    assert_eq!(rules[&0], Rule::Alternatives(vec![vec![1], vec![2, 1], vec![2, 2, 2]]));
+  }
+
+  #[test]
+  fn reports_malformed_input() {
+    match Day19::parse(&InputSource::Text("0: 1 2\n1: \"a\"\n2: 1 b\n\na\n".into())) {
+      Err(Error::Parse(e)) => assert_eq!((e.day, e.line, e.column), (19, 3, 6)),
+      _ => panic!("expected a parse error"),
+    }
+  }
+}
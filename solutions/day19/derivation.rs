@@ -0,0 +1,238 @@
+use super::grammar::{Grammar, Symbol};
+use std::collections::HashSet;
+
+// Which alternative of a rule matched which bytes of the message
+#[derive(Clone, Debug, PartialEq)]
+pub struct Derivation {
+  pub rule: usize,
+  pub alternative: usize,
+  pub start: usize,
+  pub end: usize,
+  pub children: Vec<Derivation>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Explanation {
+  Match(Derivation),
+  // The longest prefix that could still start a match, and the literal rules that expected
+  // the next byte there. None expect one if the message ended too early, or if the prefix
+  // is all the rules allow and bytes are left over.
+  Mismatch(usize, Vec<(usize, u8)>),
+}
+
+// (rule, alternative, start, end) for every alternative the chart completed
+type Spans = HashSet<(usize, usize, usize, usize)>;
+
+pub fn explain(grammar: &Grammar, start: usize, message: &str) -> Explanation {
+  let input = message.as_bytes();
+  let chart = grammar.chart(start, input);
+
+  let mut spans: Spans = HashSet::new();
+  for (end, items) in chart.iter().enumerate() {
+    for &(rule, alternative, dot, origin) in items {
+      if dot == grammar.alternatives(rule)[alternative].len() {
+        spans.insert((rule, alternative, origin, end));
+      }
+    }
+  }
+
+  let mut tree = Builder { grammar, input, spans: &spans, path: HashSet::new() };
+  if let Some(derivation) = tree.derive(start, 0, input.len()) {
+    return Explanation::Match(derivation);
+  }
+
+  let matched = chart.iter().rposition(|items| !items.is_empty()).unwrap_or(0);
+  let mut expected = chart[matched].iter()
+    .filter_map(|&(rule, alternative, dot, _)| match grammar.alternatives(rule)[alternative].get(dot) {
+      Some(&Symbol::Terminal(byte)) if matched < input.len() => Some((rule, byte)),
+      _ => None,
+    })
+    .collect::<Vec<_>>();
+  expected.sort_unstable();
+  expected.dedup();
+  Explanation::Mismatch(matched, expected)
+}
+
+struct Builder<'a> {
+  grammar: &'a Grammar,
+  input: &'a [u8],
+  spans: &'a Spans,
+  // Rules already being derived over the same bytes, which would only lead back here
+  path: HashSet<(usize, usize, usize)>,
+}
+
+impl<'a> Builder<'a> {
+  fn derive(&mut self, rule: usize, start: usize, end: usize) -> Option<Derivation> {
+    if !self.path.insert((rule, start, end)) {
+      return None;
+    }
+
+    let mut result = None;
+    for alternative in 0..self.grammar.alternatives(rule).len() {
+      if !self.spans.contains(&(rule, alternative, start, end)) {
+        continue;
+      }
+      let symbols = &self.grammar.alternatives(rule)[alternative];
+      if let Some(children) = self.derive_sequence(symbols, start, end) {
+        result = Some(Derivation { rule, alternative, start, end, children });
+        break;
+      }
+    }
+
+    self.path.remove(&(rule, start, end));
+    result
+  }
+
+  // Splits start..end between the symbols, trying every split the chart allows
+  fn derive_sequence(&mut self, symbols: &[Symbol], start: usize, end: usize) -> Option<Vec<Derivation>> {
+    match symbols.split_first() {
+      None => if start == end { Some(vec![]) } else { None },
+      Some((&Symbol::Terminal(byte), rest)) => {
+        if self.input.get(start) == Some(&byte) && start < end {
+          self.derive_sequence(rest, start + 1, end)
+        } else {
+          None
+        }
+      },
+      Some((&Symbol::Rule(rule), rest)) => {
+        for middle in start..=end {
+          let completed = (0..self.grammar.alternatives(rule).len())
+            .any(|alternative| self.spans.contains(&(rule, alternative, start, middle)));
+          if !completed {
+            continue;
+          }
+          if let Some(mut children) = self.derive_sequence(rest, middle, end) {
+            if let Some(child) = self.derive(rule, start, middle) {
+              children.insert(0, child);
+              return Some(children);
+            }
+          }
+        }
+        None
+      },
+    }
+  }
+}
+
+// One line per rule, indented by depth, showing the alternative used and what it matched
+pub fn format_derivation(grammar: &Grammar, message: &str, derivation: &Derivation) -> String {
+  let mut lines = vec![];
+  let mut stack = vec![(derivation, 0)];
+  while let Some((node, depth)) = stack.pop() {
+    lines.push(format!(
+      "{}{}: {} -> \"{}\"",
+      "  ".repeat(depth),
+      node.rule,
+      describe(&grammar.alternatives(node.rule)[node.alternative]),
+      String::from_utf8_lossy(&message.as_bytes()[node.start..node.end]),
+    ));
+    stack.extend(node.children.iter().rev().map(|child| (child, depth + 1)));
+  }
+  lines.join("\n")
+}
+
+pub fn format_explanation(grammar: &Grammar, message: &str, explanation: &Explanation) -> String {
+  match explanation {
+    Explanation::Match(derivation) => format_derivation(grammar, message, derivation),
+    Explanation::Mismatch(matched, expected) if expected.is_empty() && *matched < message.len() => format!(
+      "No match: \"{}\" fits the rules, then at byte {} found an unexpected '{}' where the message should end",
+      String::from_utf8_lossy(&message.as_bytes()[..*matched]),
+      matched,
+      message.as_bytes()[*matched] as char,
+    ),
+    Explanation::Mismatch(matched, expected) if expected.is_empty() => format!(
+      "No match: all {} bytes fit the rules, but the message ends before they are complete",
+      matched,
+    ),
+    Explanation::Mismatch(matched, expected) => {
+      let expected = expected.iter()
+        .map(|&(rule, byte)| format!("rule {} expected '{}'", rule, byte as char))
+        .collect::<Vec<_>>();
+      format!(
+        "No match: \"{}\" fits the rules, then at byte {} found '{}' where {}",
+        String::from_utf8_lossy(&message.as_bytes()[..*matched]),
+        matched,
+        message.as_bytes()[*matched] as char,
+        expected.join(", "),
+      )
+    },
+  }
+}
+
+// An alternative as written in the rules
+fn describe(symbols: &[Symbol]) -> String {
+  let mut parts: Vec<String> = vec![];
+  let mut literal = String::new();
+  for symbol in symbols {
+    match symbol {
+      Symbol::Terminal(byte) => literal.push(*byte as char),
+      Symbol::Rule(rule) => {
+        if !literal.is_empty() {
+          parts.push(format!("\"{}\"", literal));
+          literal.clear();
+        }
+        parts.push(rule.to_string());
+      },
+    }
+  }
+  if !literal.is_empty() {
+    parts.push(format!("\"{}\"", literal));
+  }
+  parts.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::day19::Day19;
+  use crate::helpers::InputSource;
+  use crate::solution::Solution;
+
+  fn example() -> Grammar {
+    let (rules, _) = Day19::parse(&InputSource::File("input/test/day19".into())).unwrap();
+    Grammar::new(&rules)
+  }
+
+  #[test]
+  fn shows_how_a_message_matched() {
+    let grammar = example();
+    let explanation = explain(&grammar, 0, "ababbb");
+    assert_eq!(format_explanation(&grammar, "ababbb", &explanation), [
+      "0: 4 1 5 -> \"ababbb\"",
+      "  4: \"a\" -> \"a\"",
+      "  1: 3 2 -> \"babb\"",
+      "    3: 5 4 -> \"ba\"",
+      "      5: \"b\" -> \"b\"",
+      "      4: \"a\" -> \"a\"",
+      "    2: 5 5 -> \"bb\"",
+      "      5: \"b\" -> \"b\"",
+      "      5: \"b\" -> \"b\"",
+      "  5: \"b\" -> \"b\"",
+    ].join("\n"));
+  }
+
+  #[test]
+  fn shows_where_matching_failed() {
+    let grammar = example();
+    assert_eq!(explain(&grammar, 0, "aaabbb"), Explanation::Mismatch(4, vec![(4, b'a')]));
+    assert_eq!(
+      format_explanation(&grammar, "aaabbb", &explain(&grammar, 0, "aaabbb")),
+      "No match: \"aaab\" fits the rules, then at byte 4 found 'b' where rule 4 expected 'a'",
+    );
+    assert_eq!(explain(&grammar, 0, "abab"), Explanation::Mismatch(4, vec![]));
+    assert_eq!(
+      format_explanation(&grammar, "abab", &explain(&grammar, 0, "abab")),
+      "No match: all 4 bytes fit the rules, but the message ends before they are complete",
+    );
+  }
+
+  #[test]
+  fn shows_bytes_left_over_after_a_match() {
+    let grammar = example();
+    assert_eq!(explain(&grammar, 0, "ababbba"), Explanation::Mismatch(6, vec![]));
+    assert_eq!(
+      format_explanation(&grammar, "ababbba", &explain(&grammar, 0, "ababbba")),
+      "No match: \"ababbb\" fits the rules, then at byte 6 found an unexpected 'a' where the message should end",
+    );
+  }
+}
@@ -0,0 +1,196 @@
+use super::grammar::{Grammar, Symbol};
+use std::collections::{BTreeSet, HashMap};
+
+// A set of NFA states, which becomes a single DFA state
+type StateSet = BTreeSet<usize>;
+
+// Each use of a rule gets its own NFA states, so rules that share sub-rules can grow
+// exponentially. Past this many NFA or DFA states compiling gives up.
+pub const MAX_STATES: usize = 100_000;
+
+// The rules from `start` as a deterministic automaton, one transition per byte
+#[derive(Clone, Debug)]
+pub struct Dfa {
+  transitions: Vec<HashMap<u8, usize>>,
+  accepting: Vec<bool>,
+}
+
+// Byte transitions and empty moves between states, built rule by rule
+struct Nfa {
+  transitions: Vec<Vec<(u8, usize)>>,
+  empty: Vec<Vec<usize>>,
+}
+
+impl Nfa {
+  // None once there are MAX_STATES states
+  fn state(&mut self) -> Option<usize> {
+    if self.transitions.len() >= MAX_STATES {
+      return None;
+    }
+    self.transitions.push(vec![]);
+    self.empty.push(vec![]);
+    Some(self.transitions.len() - 1)
+  }
+
+  // Links `from` to `to` through every way of matching `rule`. Each use of a rule gets fresh
+  // states, so this only terminates for rules without recursion.
+  fn add_rule(&mut self, grammar: &Grammar, rule: usize, from: usize, to: usize) -> Option<()> {
+    for symbols in grammar.alternatives(rule) {
+      if symbols.is_empty() {
+        self.empty[from].push(to);
+      }
+      let mut current = from;
+      for (i, symbol) in symbols.iter().enumerate() {
+        let next = if i + 1 == symbols.len() { to } else { self.state()? };
+        match *symbol {
+          Symbol::Terminal(byte) => self.transitions[current].push((byte, next)),
+          Symbol::Rule(inner) => self.add_rule(grammar, inner, current, next)?,
+        }
+        current = next;
+      }
+    }
+    Some(())
+  }
+
+  fn closure(&self, states: impl IntoIterator<Item=usize>) -> StateSet {
+    let mut closure = StateSet::new();
+    let mut stack: Vec<usize> = states.into_iter().collect();
+    while let Some(state) = stack.pop() {
+      if closure.insert(state) {
+        stack.extend(&self.empty[state]);
+      }
+    }
+    closure
+  }
+}
+
+impl Dfa {
+  // None if the rules are recursive and so may not be regular, or if either automaton would
+  // need more than MAX_STATES states
+  pub fn compile(grammar: &Grammar, start: usize) -> Option<Dfa> {
+    if grammar.is_recursive(start) {
+      return None;
+    }
+
+    let mut nfa = Nfa { transitions: vec![], empty: vec![] };
+    let (initial, last) = (nfa.state()?, nfa.state()?);
+    nfa.add_rule(grammar, start, initial, last)?;
+
+    // Subset construction: each reachable set of NFA states becomes one DFA state
+    let mut dfa = Dfa { transitions: vec![], accepting: vec![] };
+    let mut ids: HashMap<StateSet, usize> = HashMap::new();
+    let mut sets: Vec<StateSet> = vec![];
+    let first = nfa.closure(vec![initial]);
+    ids.insert(first.clone(), 0);
+    sets.push(first);
+
+    let mut i = 0;
+    while i < sets.len() {
+      let mut moves: HashMap<u8, Vec<usize>> = HashMap::new();
+      for &state in &sets[i] {
+        for &(byte, next) in &nfa.transitions[state] {
+          moves.entry(byte).or_default().push(next);
+        }
+      }
+
+      let mut transitions = HashMap::new();
+      for (byte, targets) in moves {
+        let set = nfa.closure(targets);
+        let id = match ids.get(&set) {
+          Some(&id) => id,
+          None if sets.len() >= MAX_STATES => return None,
+          None => {
+            ids.insert(set.clone(), sets.len());
+            sets.push(set);
+            sets.len() - 1
+          },
+        };
+        transitions.insert(byte, id);
+      }
+      dfa.transitions.push(transitions);
+      dfa.accepting.push(sets[i].contains(&last));
+      i += 1;
+    }
+
+    Some(dfa)
+  }
+
+  pub fn states(&self) -> usize {
+    self.transitions.len()
+  }
+
+  pub fn matches(&self, message: &str) -> bool {
+    let mut state = 0;
+    for byte in message.bytes() {
+      match self.transitions[state].get(&byte) {
+        Some(&next) => state = next,
+        None => return false,
+      }
+    }
+    self.accepting[state]
+  }
+}
+
+// An anchored regular expression for the rules from `start`, or None if they are recursive
+pub fn to_regex(grammar: &Grammar, start: usize) -> Option<String> {
+  if grammar.is_recursive(start) {
+    return None;
+  }
+  let mut patterns = HashMap::new();
+  Some(format!("^{}$", rule_pattern(grammar, start, &mut patterns)))
+}
+
+fn rule_pattern(grammar: &Grammar, rule: usize, patterns: &mut HashMap<usize, String>) -> String {
+  if let Some(pattern) = patterns.get(&rule) {
+    return pattern.clone();
+  }
+
+  let alternatives = grammar.alternatives(rule).iter()
+    .map(|symbols| symbols.iter().map(|symbol| match *symbol {
+      Symbol::Terminal(byte) => escape(byte),
+      Symbol::Rule(inner) => rule_pattern(grammar, inner, patterns),
+    }).collect::<String>())
+    .collect::<Vec<_>>();
+  let pattern = match alternatives.len() {
+    // A rule that can't match anything
+    0 => "[^\\s\\S]".to_string(),
+    1 => alternatives[0].clone(),
+    _ => format!("(?:{})", alternatives.join("|")),
+  };
+
+  patterns.insert(rule, pattern.clone());
+  pattern
+}
+
+fn escape(byte: u8) -> String {
+  let c = byte as char;
+  if "\\.+*?()|[]{}^$".contains(c) { format!("\\{}", c) } else { c.to_string() }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::day19::Day19;
+  use crate::helpers::InputSource;
+  use crate::solution::Solution;
+
+  fn example() -> (Grammar, Vec<String>) {
+    let (rules, messages) = Day19::parse(&InputSource::File("input/test/day19".into())).unwrap();
+    (Grammar::new(&rules), messages)
+  }
+
+  #[test]
+  fn agrees_with_the_general_matcher() {
+    let (grammar, messages) = example();
+    let dfa = Dfa::compile(&grammar, 0).unwrap();
+    for message in messages.iter().map(String::as_str).chain(vec!["", "a", "abbbbb", "ababbbb"]) {
+      assert_eq!(dfa.matches(message), grammar.matches(0, message), "{}", message);
+    }
+  }
+
+  #[test]
+  fn writes_a_regex() {
+    let (grammar, _) = example();
+    assert_eq!(to_regex(&grammar, 0).unwrap(), "^a(?:(?:aa|bb)(?:ab|ba)|(?:ab|ba)(?:aa|bb))b$");
+  }
+}
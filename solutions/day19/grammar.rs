@@ -0,0 +1,205 @@
+use super::{Rule, RuleMap};
+use std::collections::{HashMap, HashSet};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Symbol {
+  Terminal(u8),
+  Rule(usize),
+}
+
+// (rule, alternative, symbols matched so far, where the rule started)
+pub type Item = (usize, usize, usize, usize);
+
+// The rules as a context-free grammar: every rule is a list of alternatives, each a sequence
+// of symbols. Literals become one terminal per byte and missing rules have no alternatives.
+#[derive(Clone, Debug)]
+pub struct Grammar {
+  rules: Vec<Vec<Vec<Symbol>>>,
+  nullable: Vec<bool>,
+}
+
+impl Grammar {
+  pub fn new(rule_map: &RuleMap) -> Grammar {
+    let size = rule_map.keys().map(|&rule| rule + 1).max().unwrap_or(0);
+    let mut rules = vec![vec![]; size];
+    for (&number, rule) in rule_map {
+      rules[number] = match rule {
+        Rule::Literal(text) => vec![text.bytes().map(Symbol::Terminal).collect()],
+        Rule::Alternatives(alternatives) => alternatives.iter()
+          .map(|sequence| sequence.iter().map(|&rule| Symbol::Rule(rule)).collect())
+          .collect(),
+      };
+    }
+
+    let nullable = nullable_rules(&rules);
+    Grammar { rules, nullable }
+  }
+
+  pub fn alternatives(&self, rule: usize) -> &[Vec<Symbol>] {
+    self.rules.get(rule).map_or(&[], |alternatives| alternatives.as_slice())
+  }
+
+  // Whether some rule reachable from `start` refers back to itself, directly or not
+  pub fn is_recursive(&self, start: usize) -> bool {
+    // 0: not visited, 1: on the current path, 2: done
+    let mut state = vec![0u8; self.rules.len().max(start + 1)];
+    let mut stack = vec![(start, false)];
+    while let Some((rule, leaving)) = stack.pop() {
+      if leaving {
+        state[rule] = 2;
+        continue;
+      }
+      match state[rule] {
+        1 => return true,
+        2 => continue,
+        _ => (),
+      }
+      state[rule] = 1;
+      stack.push((rule, true));
+      for symbol in self.alternatives(rule).iter().flatten() {
+        if let Symbol::Rule(next) = *symbol {
+          match state.get(next) {
+            Some(1) => return true,
+            Some(0) => stack.push((next, false)),
+            _ => (),
+          }
+        }
+      }
+    }
+    false
+  }
+
+  fn nullable(&self, rule: usize) -> bool {
+    self.nullable.get(rule).copied().unwrap_or(false)
+  }
+
+  pub fn matches(&self, start: usize, message: &str) -> bool {
+    let chart = self.chart(start, message.as_bytes());
+    chart[message.len()].iter().any(|&(rule, alternative, dot, origin)| {
+      rule == start && origin == 0 && dot == self.alternatives(rule)[alternative].len()
+    })
+  }
+
+  // Earley recognition: chart[i] holds every partly matched alternative that could still be
+  // part of a match of `start` after reading the first i bytes. Left recursion and ambiguity
+  // are fine, and a nullable rule is stepped over as soon as it is predicted.
+  pub fn chart(&self, start: usize, input: &[u8]) -> Vec<Vec<Item>> {
+    let mut chart = Chart::new(self, input.len());
+    for alternative in 0..self.alternatives(start).len() {
+      chart.add(0, (start, alternative, 0, 0));
+    }
+
+    for i in 0..=input.len() {
+      let mut j = 0;
+      while j < chart.items[i].len() {
+        let (rule, alternative, dot, origin) = chart.items[i][j];
+        match self.alternatives(rule)[alternative].get(dot) {
+          Some(&Symbol::Terminal(byte)) => {
+            if input.get(i) == Some(&byte) {
+              chart.add(i + 1, (rule, alternative, dot + 1, origin));
+            }
+          },
+          Some(&Symbol::Rule(next)) => {
+            for next_alternative in 0..self.alternatives(next).len() {
+              chart.add(i, (next, next_alternative, 0, i));
+            }
+            if self.nullable(next) {
+              chart.add(i, (rule, alternative, dot + 1, origin));
+            }
+          },
+          None => {
+            let parents = chart.waiting[origin].get(&rule).cloned().unwrap_or_default();
+            for (r, a, d, o) in parents {
+              chart.add(i, (r, a, d + 1, o));
+            }
+          },
+        }
+        j += 1;
+      }
+    }
+
+    chart.items
+  }
+}
+
+struct Chart<'a> {
+  grammar: &'a Grammar,
+  items: Vec<Vec<Item>>,
+  seen: Vec<HashSet<Item>>,
+  // The items in items[i] that are waiting for each rule, so completing a rule is a lookup
+  waiting: Vec<HashMap<usize, Vec<Item>>>,
+}
+
+impl<'a> Chart<'a> {
+  fn new(grammar: &'a Grammar, length: usize) -> Chart<'a> {
+    Chart {
+      grammar,
+      items: vec![vec![]; length + 1],
+      seen: vec![HashSet::new(); length + 1],
+      waiting: vec![HashMap::new(); length + 1],
+    }
+  }
+
+  fn add(&mut self, i: usize, item: Item) {
+    if self.seen[i].insert(item) {
+      let (rule, alternative, dot, _) = item;
+      if let Some(&Symbol::Rule(next)) = self.grammar.alternatives(rule)[alternative].get(dot) {
+        self.waiting[i].entry(next).or_default().push(item);
+      }
+      self.items[i].push(item);
+    }
+  }
+}
+
+// Rules that can match the empty string, found by iterating to a fixed point
+fn nullable_rules(rules: &[Vec<Vec<Symbol>>]) -> Vec<bool> {
+  let mut nullable = vec![false; rules.len()];
+  let mut changed = true;
+  while changed {
+    changed = false;
+    for (rule, alternatives) in rules.iter().enumerate() {
+      if !nullable[rule] && alternatives.iter().any(|sequence| sequence.iter().all(|symbol| match symbol {
+        Symbol::Terminal(_) => false,
+        Symbol::Rule(other) => nullable.get(*other).copied().unwrap_or(false),
+      })) {
+        nullable[rule] = true;
+        changed = true;
+      }
+    }
+  }
+  nullable
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::day19::parse_rules;
+  use crate::error::SourceLine;
+
+  fn grammar(text: &str) -> Grammar {
+    let lines: Vec<String> = text.lines().map(String::from).collect();
+    let rules = parse_rules(&mut SourceLine::numbered(19, &lines)).unwrap();
+    Grammar::new(&rules)
+  }
+
+  #[test]
+  fn matches_left_recursion_and_many_alternatives() {
+    // 0 is one or more of a, b or c, built up from the left
+    let g = grammar("0: 1 | 0 1\n1: 2 | 3 | 4\n2: \"a\"\n3: \"b\"\n4: \"c\"");
+    assert!(g.matches(0, "a"));
+    assert!(g.matches(0, "cabbac"));
+    assert!(!g.matches(0, ""));
+    assert!(!g.matches(0, "abd"));
+    assert!(g.is_recursive(0));
+    assert!(!g.is_recursive(1));
+  }
+
+  #[test]
+  fn steps_over_empty_rules() {
+    // 0 is a^n b^n, with 2 matching nothing
+    let g = grammar("0: 1 0 3 | 2\n1: \"a\"\n2: \n3: \"b\"");
+    assert!(g.matches(0, ""));
+    assert!(g.matches(0, "aaabbb"));
+    assert!(!g.matches(0, "aabbb"));
+  }
+}
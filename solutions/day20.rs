@@ -1,469 +1,324 @@
-use crate::helpers;
-use std::error::Error;
+use crate::helpers::{self, Grid, InputSource};
+use crate::solution::{Answer, Solution};
+use crate::error::{Error, ParseError, SourceLine};
+use assembly::Jigsaw;
+use export::Pixel;
+use patterns::{Pattern, PatternMatch};
+use std::fmt;
 
-const INPUT_FILE: &str = "input/day20";
-const SEAMONSTER_FILE: &str = "input/day20_seamonster";
+pub mod assembly;
+pub mod export;
+pub mod patterns;
 
+pub const INPUT_FILE: &str = "input/day20";
 
-type Tile = (u32, Vec<Vec<bool>>);
+// Built in, so the puzzle can be solved from any working directory
+const SEA_MONSTER: &str = include_str!("../input/day20_seamonster");
 
-#[derive(Clone, PartialEq, Copy, Debug)]
-enum Direction {
-  N,
-  E,
-  S,
-  W
-}
-
-type OrientedTile<'a> = (&'a Tile, Direction, bool);
+const DAY: u8 = 20;
 
-type Coord = (usize, usize);
+// The puzzle's tiles have a one pixel border around their part of the picture
+pub const BORDER: usize = 1;
 
-type PartialImage<'a> = Vec<Vec<Option<OrientedTile<'a>>>>;
+pub type Tile = (u32, Grid<bool>);
 
-type CompletedImage = Vec<Vec<bool>>;
+// Each place holds a tile's ID and the tile turned the way it fits
+pub type PartialImage = Grid<(u32, Grid<bool>)>;
 
-impl Direction {
-  fn from_degrees(degrees: i32) -> Direction {
-    match degrees.rem_euclid(360) {
-      0 => Direction::N,
-      90 => Direction::E,
-      180 => Direction::S,
-      270 => Direction::W,
-      _ => {
-        println!("Unknown direction! {}", degrees);
-        Direction::N
-      }
-    }
-  }
+pub type CompletedImage = Grid<bool>;
 
-  fn in_degrees(&self) -> i32 {
-    match self {
-      Direction::N => 0,
-      Direction::E => 90,
-      Direction::S => 180,
-      Direction::W => 270,
-    }
-  }
-
-  fn as_vector(&self) -> (i32, i32) {
-    match self {
-      Direction::N => (0, 1),
-      Direction::E => (1, 0),
-      Direction::S => (0, -1),
-      Direction::W => (-1, 0),
-    }
-  }
-
-  fn rotate_right (&self, degrees: i32) -> Direction {
-    Direction::from_degrees(self.in_degrees() + degrees)
-  }
+#[derive(Clone, Debug, PartialEq)]
+pub enum ImageError {
+  NoTiles,
+  // No rectangle of this many tiles has every edge matching its neighbour's
+  NoArrangement(usize),
+  // (border, tile width, tile height) when trimming the border would leave nothing
+  BorderTooWide(usize, usize, usize),
+}
 
-  fn mirror (&self) -> Direction {
+impl fmt::Display for ImageError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     match self {
-      Direction::N => Direction::N,
-      Direction::E => Direction::W,
-      Direction::S => Direction::S,
-      Direction::W => Direction::E,
+      ImageError::NoTiles => write!(f, "there are no tiles to assemble"),
+      ImageError::NoArrangement(count) => write!(f, "the {} tiles don't fit together into a rectangle", count),
+      ImageError::BorderTooWide(border, width, height) => write!(
+        f, "trimming a {} pixel border leaves nothing of the {}x{} tiles", border, width, height,
+      ),
     }
   }
 }
 
-fn border((tile, orientation, mirrored): &OrientedTile, direction: Direction) -> Vec<bool> {
-  let rotated_dir = direction.rotate_right(orientation.in_degrees());
-  let rotated_dir = if *mirrored {rotated_dir.mirror()} else {rotated_dir};
-
-  let x_max = tile.1.len();
-  let y_max = tile.1[0].len();
+pub struct Day20;
 
-  let (mut xs, mut ys) = match rotated_dir {
-    Direction::N => (vec![0], (0..y_max).collect()),
-    Direction::E => ((0..x_max).collect(), vec![y_max-1]),
-    Direction::S => (vec![x_max-1], (0..y_max).collect()),
-    Direction::W => ((0..x_max).collect(), vec![0]),
-  };
+impl Solution for Day20 {
+  type Input = Vec<Tile>;
 
-  if rotated_dir == Direction::S || rotated_dir == Direction::W {
-    xs.reverse();
-    ys.reverse();
+  fn parse(input: &InputSource) -> Result<Self::Input, Error> {
+    parse_input(input)
   }
 
-  if *mirrored {
-    xs.reverse();
-    ys.reverse();
+  // Tiles that don't fit together fail either part with an ImageError
+  fn part_one(tiles: &Self::Input) -> Result<Answer, Error> {
+    Ok(solve_part_one(&assemble_image(tiles)?))
   }
 
-  let mut border = vec![];
-
-  for &x in &xs {
-    for &y in &ys {
-      border.push(tile.1[x][y]);
-    }
+  fn part_two(tiles: &Self::Input) -> Result<Answer, Error> {
+    let (_, roughness) = search_patterns(&assemble_image(tiles)?, &[sea_monster()], BORDER)?;
+    Ok((roughness as u64).into())
   }
-
-  border
-}
-
-pub fn solve() -> Result<(), Box<dyn Error>> {
-  let tiles = parse_input()?;
-  let partial_image = solve_part_one(&tiles);
-  let completed = convert_partial_image(&partial_image);
-  let seamonster = parse_seamonster()?;
-  solve_part_two(&completed, &seamonster);
-  /*
-  let test_border = border(&(&tiles[0], Direction::N, false), Direction::E);
-  let s = test_border.iter().map(|&p| if p {'#'} else {'.'}).collect::<String>();
-  println!("Test {}", s);
-  */
-  return Ok(());
 }
 
-fn solve_part_one(tiles: &Vec<Tile>) -> PartialImage {
-  let directions: Vec<Direction> = vec![
-    Direction::N,
-    Direction::E,
-    Direction::S,
-    Direction::W,
-  ];
-  let side_length = (tiles.len() as f64).sqrt().round() as usize;
-  let mut image: PartialImage = vec![];
-  for _ in 0..side_length {
-    let mut row = vec![];
-    for _ in 0..side_length {
-      row.push(None);
-    }
-    image.push(row);
-  }
-
-  let mut used_tiles: Vec<usize> = vec![];
-
-  let mut i = 0;
-  let mut j = 0;
-  let mut d = 0;
-
-  let mut m = false;
-
-  'outer: while i < tiles.len() {
-    let (x, y) = (i / side_length, i.rem_euclid(side_length));
-
-    while j < tiles.len() {
-      if !used_tiles.contains(&j) {
-        let tile = &tiles[j];
-        while d < directions.len() {
-          let dir = directions[d];
-          let placed_tile = (tile, dir, m);
-          let result = test_tile(&image, (tile, dir, m), (x, y));
-
-          if result {
-            image[x][y] = Some(placed_tile);
-            used_tiles.push(j);
-            j = 0;
-            d = 0;
-            m = false;
-            i += 1;
-            continue 'outer;
-          }
-          if m {
-            m = false;
-            d += 1;
-          } else {
-            m = true;
-          }
-        }
-      }
-
-      j += 1;
-      d = 0;
-    }
+fn solve_part_one(image: &PartialImage) -> Answer {
+  let (right, bottom) = (image.width() - 1, image.height() - 1);
 
+  [(0, 0), (right, 0), (0, bottom), (right, bottom)].iter()
+    .map(|&point| image[point].0 as u64)
+    .product::<u64>()
+    .into()
+}
 
+pub fn assemble_image(tiles: &[Tile]) -> Result<PartialImage, ImageError> {
+  let jigsaw = Jigsaw::new(tiles);
+  let placements = jigsaw.assemble()?;
+  Ok(placements.map(|&placement| (jigsaw.id(placement), jigsaw.oriented(placement).clone())))
+}
 
-    if j == tiles.len() {
-      i -= 1;
-      let (old_x, old_y) = (i / side_length, i.rem_euclid(side_length));
-      let failed_tile = image[old_x][old_y].unwrap();
-      let old_index = used_tiles.pop().unwrap();
-
-      j = old_index;
-      
-      d = match failed_tile.1 {
-        Direction::N => 0,
-        Direction::E => 1,
-        Direction::S => 2,
-        Direction::W => 3,
-      };
-      m = failed_tile.2;
-
-      if !m {
-        m = true
-      } else if d < 3 {
-        m = false;
-        d += 1;
-      } else {
-        m = false;
-        d = 0;
-        j += 1;
-      }
+// Every match of the patterns in the picture stitched with `border` trimmed from each tile,
+// and how many set cells none covers
+pub fn search_patterns(image: &PartialImage, patterns: &[Pattern], border: usize) -> Result<(Vec<PatternMatch>, usize), ImageError> {
+  let completed = convert_partial_image(image, border)?;
+  let matches = patterns::find_matches(&completed, patterns);
+  let roughness = patterns::roughness(&completed, &patterns::coverage(&completed, patterns, &matches));
+  Ok((matches, roughness))
+}
 
-      image[old_x][old_y] = None;
-      
-    } else {
-      println!("Should not happen");
-    }
+// The picture for export, stitched with `border` trimmed from each tile and the cells the
+// patterns cover marked. With `keep_borders` every tile is kept whole, and the trimmed pixels
+// are marked as borders instead.
+pub fn render_image(image: &PartialImage, patterns: &[Pattern], border: usize, keep_borders: bool) -> Result<Grid<Pixel>, ImageError> {
+  let trimmed = convert_partial_image(image, border)?;
+  let monsters = patterns::coverage(&trimmed, patterns, &patterns::find_matches(&trimmed, patterns));
+  if !keep_borders {
+    return Ok(Grid::from_fn(trimmed.width(), trimmed.height(), |p| (trimmed[p], false, monsters[p])));
   }
 
-  println!("Part 1: {}", 
-    (image[0][0].unwrap().0.0 as u64) *
-    (image[side_length - 1][0].unwrap().0.0 as u64) *
-    (image[0][side_length-1].unwrap().0.0 as u64) *
-    (image[side_length-1][side_length-1].unwrap().0.0 as u64)
-  );
-
-  image
+  let whole = convert_partial_image(image, 0)?;
+  let (tile_width, tile_height) = (image[(0, 0)].1.width(), image[(0, 0)].1.height());
+  let (trimmed_width, trimmed_height) = (tile_width - 2 * border, tile_height - 2 * border);
+  Ok(Grid::from_fn(whole.width(), whole.height(), |(x, y)| {
+    let (i, j) = (x % tile_width, y % tile_height);
+    let in_border = i < border || j < border || i >= border + trimmed_width || j >= border + trimmed_height;
+    let covered = !in_border && monsters[(
+      x / tile_width * trimmed_width + i - border,
+      y / tile_height * trimmed_height + j - border,
+    )];
+    (whole[(x, y)], in_border, covered)
+  }))
 }
 
-fn solve_part_two(image: &CompletedImage, monster: &CompletedImage) {
-  let directions: Vec<Direction> = vec![
-    Direction::N,
-    Direction::E,
-    Direction::S,
-    Direction::W,
-  ];
-
-
-  let mut seamonsters = 0;
-  for d in directions {
-    for m in vec![false, true] {
-      let mut positions = vec![];
-      for i in 0..image.len() {
-        for j in 0..image[0].len() {
-          let found = check_for_monster(image, monster, (i, j), d, m);
-          if found {
-            positions.push((i,j));
-          }
-        }
-      }
-      if positions.len() > 0 {
-        println!("Found seamonsters at {:?} {}: {:?}", d, m, positions);
-        seamonsters = positions.len();
-        break;
-      } else {
-        println!("Found no seamonsters at {:?} {}", d, m);
-      }
-    }
+// Stitches the tiles together without `border` pixels from each of their edges. Tiles all
+// have the same size once assembled, since every row shares a height and every column a width.
+pub fn convert_partial_image(image: &PartialImage, border: usize) -> Result<CompletedImage, ImageError> {
+  let (tile_width, tile_height) = (image[(0, 0)].1.width(), image[(0, 0)].1.height());
+  if 2 * border >= tile_width.min(tile_height) {
+    return Err(ImageError::BorderTooWide(border, tile_width, tile_height));
   }
+  let (trimmed_width, trimmed_height) = (tile_width - 2 * border, tile_height - 2 * border);
 
-  let seamonster_size = monster.iter().fold(0, |res, r| res + r.iter().fold(0, |acc, &b| acc + if b {1} else {0}));
-  let image_size = image.iter().fold(0, |res, r| res + r.iter().fold(0, |acc, &b| acc + if b {1} else {0}));
-
-  println!("Part 2: {}", image_size - seamonsters * seamonster_size);
-
+  Ok(Grid::from_fn(image.width() * trimmed_width, image.height() * trimmed_height, |(x, y)| {
+    let (_, tile) = &image[(x / trimmed_width, y / trimmed_height)];
+    tile[(x % trimmed_width + border, y % trimmed_height + border)]
+  }))
 }
 
-fn check_for_monster(image: &CompletedImage, monster: &CompletedImage, (x, y): Coord, dir: Direction, m: bool) -> bool {
+fn parse_input(input: &InputSource) -> Result<Vec<Tile>, Error> {
+  let lines = helpers::read_lines(input)?.collect::<Result<Vec<_>, _>>()?;
+  let mut sources = SourceLine::numbered(DAY, &lines).peekable();
 
-  match dir {
-    Direction::N | Direction::S => if x + monster.len() > image.len() || y + monster[0].len() > image[0].len() {return false}
-    Direction::E | Direction::W => if x + monster[0].len() > image.len() || y + monster.len() > image[0].len() {return false}
+  let mut tiles: Vec<Tile> = vec![];
+  while sources.peek().is_some() {
+    let size = tiles.first().map(|(_, grid)| (grid.width(), grid.height()));
+    tiles.push(parse_tile(&mut sources, size)?)
   }
-
-  for i in 0..monster.len() {
-    // println!("");
-    for j in 0..monster[i].len() {
-      //print!("{}", if monster[i][j] {"#"} else {"."});
-
-      let (mut image_x, mut image_y) = match dir {
-        Direction::N => (i, j),
-        Direction::E => (j, monster.len() - i - 1),
-        Direction::S => (monster.len() - i - 1, monster[0].len() - j - 1),
-        Direction::W => (monster[0].len() - j- 1, i)
-      };
-      if m {
-        image_y = match dir {
-          Direction::N | Direction::S => monster[0].len() - image_y - 1,
-          Direction::E | Direction::W => monster.len() - image_y - 1,
-        }
-      }
-      
-      image_x = match dir {
-        Direction::N | Direction::S => monster.len() - image_x - 1,
-        Direction::E | Direction::W => monster[0].len() - image_x - 1,
-      };
-
-      image_x += x;
-      image_y += y;
-
-      if !image[image_x][image_y] && monster[i][j] {
-        return false
-      }
-    }
+  if tiles.is_empty() {
+    return Err(ParseError::missing_line(DAY, 1, "expected a tile").into());
   }
+  Ok(tiles)
+}
 
-  true
+// '#' marks the cells a pattern needs set and anything else is ignored, so short lines are
+// padded rather than rejected
+pub fn parse_pattern(path: &str) -> Result<Pattern, Error> {
+  let lines = helpers::read_lines(&InputSource::File(path.into()))?.collect::<Result<Vec<_>, _>>()?;
+  let pattern = pattern_grid(&lines);
+  if !pattern.iter().any(|&cell| cell) {
+    return Err(ParseError::missing_line(DAY, lines.len() + 1, &format!("expected a '#' in the pattern {}", path)).into());
+  }
+  Ok((path.to_string(), pattern))
 }
 
-fn test_tile(image: &PartialImage, tile: OrientedTile, (x, y): Coord) -> bool {
-  let max = image.len() - 1;
-  let directions: Vec<Direction> = vec![
-    Direction::N,
-    Direction::E,
-    Direction::S,
-    Direction::W,
-  ];
-
-  for dir in directions {
-    let neighbour;
-    match dir {
-      Direction::N => if x == max {continue} else {neighbour = (x+1, y)},
-      Direction::E => if y == max {continue} else {neighbour = (x, y+1)},
-      Direction::S => if x == 0 {continue} else {neighbour = (x-1, y)},
-      Direction::W => if y == 0 {continue} else {neighbour = (x, y-1)},
-    }
+pub fn sea_monster() -> Pattern {
+  let lines: Vec<String> = SEA_MONSTER.lines().map(String::from).collect();
+  ("sea monster".to_string(), pattern_grid(&lines))
+}
 
+fn pattern_grid(lines: &[String]) -> Grid<bool> {
+  let width = lines.iter().map(|line| line.chars().count()).max().unwrap_or(0);
+  let rows = lines.iter()
+    .map(|line| (0..width).map(|i| line.chars().nth(i) == Some('#')).collect())
+    .collect();
+  Grid::from_rows(rows).unwrap_or_else(|| Grid::filled(0, 0, false))
+}
 
-    if let Some(neighbour_tile) = &image[neighbour.0][neighbour.1] {
-      let neighbour_border = border(&neighbour_tile, dir.rotate_right(180));
-      let this_border = border(&tile, dir);
-      if !neighbour_border.iter().eq(this_border.iter().rev()) {
-        return false;
-      }
-    }
-  }
+// Every tile must be the same size as the first, given as (width, height)
+fn parse_tile<'a>(sources: &mut impl Iterator<Item=SourceLine<'a>>, size: Option<(usize, usize)>) -> Result<Tile, ParseError> {
+  // First: ID
+  // Then: tile rows
+  let id_line = match sources.next() {
+    Some(source) => source,
+    None => return Err(ParseError::missing_line(DAY, 1, "expected a tile")),
+  };
+  let id_part = id_line.text.trim().trim_start_matches("Tile ").trim_end_matches(':');
+  let id = id_line.parse::<u32>(id_part)?;
 
-  true
-} 
+  let tile_rows = sources.take_while(|source| !source.text.trim().is_empty());
 
-fn convert_partial_image (image: &PartialImage) -> CompletedImage {
-  let mut completed = vec![];
-  let tile_side = image[0][0].unwrap().0.1.len();
-  let completed_tile_side = tile_side - 2;
-  let completed_side = image.len() * completed_tile_side;
+  let grid: Grid<bool> = Grid::parse(tile_rows)?;
 
-  for _ in 0..completed_side {
-    completed.push(vec![false; completed_side]);
+  let (width, height) = (grid.width(), grid.height());
+  if width == 0 || height == 0 {
+    return Err(id_line.error("", &format!("expected the rows of tile {}", id)));
   }
-
-  for x in 0..image.len() {
-    for y in 0..image[x].len() {
-      let (tile, dir, m) = image[x][y].unwrap();
-      for i in 1..tile_side-1 {
-        for j in 1..tile_side-1 {
-
-          let mut tile_coords = match dir {
-            Direction::N => (i, j),
-            Direction::E => (tile_side - j - 1, i),
-            Direction::S => (tile_side - i - 1, tile_side - j - 1),
-            Direction::W => (j, tile_side - i - 1),
-          };
-          if m {
-            tile_coords = (tile_coords.0, tile_side - tile_coords.1 - 1);
-          }
-          tile_coords = (tile_side - tile_coords.0 - 1, tile_coords.1);
-          
-          let completed_coords = (completed_tile_side * x + i - 1, completed_tile_side * y + j - 1);
-          completed[completed_coords.0][completed_coords.1] = tile.1[tile_coords.0][tile_coords.1];
-        }
-      }
+  if let Some((expected_width, expected_height)) = size {
+    if (width, height) != (expected_width, expected_height) {
+      return Err(id_line.error(id_part, &format!(
+        "tile {} is {}x{}, but the first tile is {}x{}", id, width, height, expected_width, expected_height,
+      )));
     }
   }
 
-  for r in &completed {
-    for c in r {
-      print!("{}", if *c {"#"} else {"."});
-    }
-    println!("");
-  }
-  completed
+  Ok((id, grid))
 }
 
+#[cfg(test)]
+mod tests {
+  use super::*;
 
-fn print_solution_1 (image: &PartialImage) {
-  let mut completed = vec![];
-  let tile_side = image[0][0].unwrap().0.1.len();
-  let completed_tile_side = tile_side;
-  let completed_side = image.len() * completed_tile_side;
-
-  for _ in 0..completed_side {
-    completed.push(vec![false; completed_side]);
+  #[test]
+  fn solves_example() {
+    let input = Day20::parse(&InputSource::File("input/test/day20".into())).unwrap();
+    assert_eq!(Day20::part_one(&input).unwrap(), Answer::Int(20899048083289));
+    assert_eq!(Day20::part_two(&input).unwrap(), Answer::Int(273));
   }
 
-  for x in 0..image.len() {
-    for y in 0..image[x].len() {
-      let (tile, dir, m) = image[x][y].unwrap();
-      if x == 2 && y == 2 {
-        println!("Test {} {:?} {}", m, dir, tile.0);
-      }
-      for i in 0..tile_side {
-        for j in 0..tile_side {
-
-          let mut tile_coords = match dir {
-            Direction::N => (i, j),
-            Direction::E => (tile_side - j - 1, i),
-            Direction::S => (tile_side - i - 1, tile_side - j - 1),
-            Direction::W => (j, tile_side - i - 1),
-          };
-          if m {
-            tile_coords = (tile_coords.0, tile_side - tile_coords.1 - 1);
-          }
-          tile_coords = (tile_side - tile_coords.0 - 1, tile_coords.1);
-          
-          let completed_coords = (completed_tile_side * x + i, completed_tile_side * y + j);
-          completed[completed_coords.0][completed_coords.1] = tile.1[tile_coords.0][tile_coords.1];
-        }
-      }
-    }
+  // Cuts a noisy picture into `columns` x `rows` tiles of the given size, with neighbours
+  // sharing their edge pixels like the puzzle's, and turns them every which way
+  fn cut_picture(columns: usize, rows: usize, (width, height): (usize, usize)) -> (Vec<Tile>, Grid<bool>) {
+    let picture = Grid::from_fn(columns * (width - 1) + 1, rows * (height - 1) + 1, |(x, y)| {
+      let mut hash = ((x as u64) << 32 | y as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+      hash = (hash ^ hash >> 31).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+      hash >> 29 & 1 == 1
+    });
+    let tiles = (0..columns * rows).map(|i| {
+      let (left, top) = (i % columns * (width - 1), i / columns * (height - 1));
+      let tile = Grid::from_fn(width, height, |(x, y)| picture[(left + x, top + y)]);
+      (1000 + i as u32, tile.orientations().swap_remove(i * 5 % 8))
+    }).collect();
+    (tiles, picture)
   }
 
-  for r in &completed {
-    for c in r {
-      print!("{}", if *c {"#"} else {"."});
-    }
-    println!("");
+  // What's left of a picture from cut_picture once every tile loses `border` pixels all round
+  fn trim_picture(picture: &Grid<bool>, columns: usize, rows: usize, (width, height): (usize, usize), border: usize) -> CompletedImage {
+    let (trimmed_width, trimmed_height) = (width - 2 * border, height - 2 * border);
+    Grid::from_fn(columns * trimmed_width, rows * trimmed_height, |(x, y)| picture[(
+      x / trimmed_width * (width - 1) + x % trimmed_width + border,
+      y / trimmed_height * (height - 1) + y % trimmed_height + border,
+    )])
   }
-}
-      
-fn parse_input() -> Result<Vec<Tile>, Box<dyn Error>> {
-  let mut lines = helpers::read_lines(INPUT_FILE)?.map(|l| l.unwrap()).peekable();
 
-  let mut tiles = vec![];
-  while let Some(_) = lines.peek() {
-    tiles.push(parse_tile(&mut lines)?)
+  #[test]
+  fn assembles_rectangles_of_rectangular_tiles() {
+    let (tiles, _) = cut_picture(4, 2, (12, 9));
+    let image = assemble_image(&tiles).unwrap();
+    assert_eq!(image.width() * image.height(), 8);
+    assert_eq!(solve_part_one(&image), Answer::Int(1000 * 1003 * 1004 * 1007));
+    assert_eq!(Jigsaw::new(&tiles[..7]).assemble(), Err(ImageError::NoArrangement(7)));
+    // Edges longer than a machine word
+    let (tiles, picture) = cut_picture(3, 2, (150, 70));
+    let image = assemble_image(&tiles).unwrap();
+    assert_eq!(solve_part_one(&image), Answer::Int(1000 * 1002 * 1003 * 1005));
+    assert!(trim_picture(&picture, 3, 2, (150, 70), BORDER).orientations().contains(&convert_partial_image(&image, BORDER).unwrap()));
   }
 
-  Ok(tiles)
-}
-
-fn parse_seamonster() -> Result<CompletedImage, Box<dyn Error>> {
-  let mut lines = helpers::read_lines(SEAMONSTER_FILE)?.map(|l| l.unwrap());
+  #[test]
+  fn trims_any_border() {
+    let (tiles, picture) = cut_picture(4, 2, (12, 9));
+    let image = assemble_image(&tiles).unwrap();
+    for &border in &[0, BORDER, 2] {
+      let completed = convert_partial_image(&image, border).unwrap();
+      assert!(trim_picture(&picture, 4, 2, (12, 9), border).orientations().contains(&completed));
+    }
+    assert_eq!(convert_partial_image(&image, 0).unwrap().iter().count(), 8 * 12 * 9);
 
-  let mut monster = vec![];
-  for line in lines {
-    monster.push(line.chars().map(|c| c == '#').collect());
+    let (tile_width, tile_height) = (image[(0, 0)].1.width(), image[(0, 0)].1.height());
+    assert_eq!(convert_partial_image(&image, 5), Err(ImageError::BorderTooWide(5, tile_width, tile_height)));
+    assert_eq!(search_patterns(&image, &[], 5), Err(ImageError::BorderTooWide(5, tile_width, tile_height)));
   }
 
-  Ok(monster)
-}
-
-fn parse_tile(lines: &mut impl Iterator<Item=String>) -> Result<Tile, Box<dyn Error>> {
-  // First: ID
-  // Then: tile rows
-  let id_line = lines.next().unwrap();
-  let id = id_line.chars().filter(|c| c.is_digit(10)).collect::<String>().parse::<u32>()?;
+  #[test]
+  fn renders_monsters_with_and_without_borders() {
+    let image = assemble_image(&Day20::parse(&InputSource::File("input/test/day20".into())).unwrap()).unwrap();
+    let patterns = vec![sea_monster()];
+    let trimmed = render_image(&image, &patterns, BORDER, false).unwrap();
+    assert_eq!((trimmed.width(), trimmed.height()), (24, 24));
+    assert_eq!(trimmed.iter().filter(|pixel| pixel.2).count(), 2 * 15);
+    assert!(trimmed.iter().all(|&(set, border, monster)| !border && (set || !monster)));
+
+    let whole = render_image(&image, &patterns, BORDER, true).unwrap();
+    assert_eq!((whole.width(), whole.height()), (30, 30));
+    assert_eq!(whole.iter().filter(|pixel| pixel.1).count(), 9 * (100 - 64));
+    assert_eq!(whole.iter().filter(|pixel| pixel.2).count(), 2 * 15);
+
+    // A wider border marks more of each tile, and none trims nothing
+    let thin = render_image(&image, &patterns, 2, true).unwrap();
+    assert_eq!((thin.width(), thin.height()), (30, 30));
+    assert_eq!(thin.iter().filter(|pixel| pixel.1).count(), 9 * (100 - 36));
+    assert_eq!(render_image(&image, &patterns, 0, false).unwrap().width(), 30);
+  }
 
-  let mut tile_rows: Vec<Vec<bool>> = vec![];
+  #[test]
+  fn searches_several_patterns_without_counting_overlaps_twice() {
+    let image = assemble_image(&Day20::parse(&InputSource::File("input/test/day20".into())).unwrap()).unwrap();
+    let mut patterns = vec![sea_monster()];
+    let (matches, roughness) = search_patterns(&image, &patterns, BORDER).unwrap();
+    assert_eq!((matches.len(), roughness), (2, 273));
+
+    // The same monster again under another name finds the same cells
+    patterns.push(("copy".to_string(), patterns[0].1.clone()));
+    let (both, roughness) = search_patterns(&image, &patterns, BORDER).unwrap();
+    assert_eq!((both.len(), roughness), (4, 273));
+    assert!(both.iter().filter(|found| found.0 == 1).all(|&(_, orientation, point)| matches.contains(&(0, orientation, point))));
+  }
 
-  while let Some(l) = lines.next() {
-    if l.trim().len() == 0 {
-      break;
+  #[test]
+  fn reports_tiles_that_do_not_fit_together() {
+    let input = "Tile 1:\n#..\n...\n...\n\nTile 2:\n.##\n#.#\n###\n";
+    let tiles = Day20::parse(&InputSource::Text(input.into())).unwrap();
+    for result in &[Day20::part_one(&tiles), Day20::part_two(&tiles)] {
+      match result {
+        Err(Error::Image(error)) => assert_eq!(*error, ImageError::NoArrangement(2)),
+        _ => panic!("expected an image error"),
+      }
     }
-
-    let tile_points: Vec<bool> = l.chars().map(|c| match c {
-      '#' => true,
-      _ => false
-    }).collect();
-
-    tile_rows.push(tile_points);
   }
 
-  Ok((id, tile_rows))
-}
\ No newline at end of file
+  #[test]
+  fn rejects_tiles_of_different_sizes() {
+    let input = "Tile 1:\n#.#\n.#.\n\nTile 2:\n##\n..\n";
+    let error = match Day20::parse(&InputSource::Text(input.into())) {
+      Err(Error::Parse(error)) => error,
+      _ => panic!("expected a parse error"),
+    };
+    assert_eq!((error.line, error.message.as_str()), (5, "tile 2 is 2x2, but the first tile is 3x2"));
+  }
+}
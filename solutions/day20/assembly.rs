@@ -0,0 +1,277 @@
+use super::{ImageError, Tile};
+use crate::helpers::Grid;
+use std::collections::HashMap;
+
+// An edge read as bits, left to right or top to bottom and 64 to a word, and its length in
+// pixels. The last word holds whatever bits are left over.
+pub type Edge = (Box<[u64]>, usize);
+
+// Top, right, bottom and left edges of a tile in one orientation
+type Edges = [Edge; 4];
+
+const TOP: usize = 0;
+const RIGHT: usize = 1;
+const BOTTOM: usize = 2;
+const LEFT: usize = 3;
+
+// A tile index and one of the eight orientations from Grid::orientations
+pub type Placement = (usize, usize);
+
+// The tiles with every orientation and edge worked out once, and which tiles share each edge.
+// Tiles may be rectangles of any size, but should all be the same size.
+pub struct Jigsaw<'a> {
+  tiles: &'a [Tile],
+  orientations: Vec<Vec<Grid<bool>>>,
+  edges: Vec<Vec<Edges>>,
+  // Keyed by the canonical edge, so an edge finds its neighbour whichever way it is read
+  index: HashMap<Edge, Vec<usize>>,
+}
+
+impl<'a> Jigsaw<'a> {
+  pub fn new(tiles: &'a [Tile]) -> Jigsaw<'a> {
+    let orientations: Vec<Vec<Grid<bool>>> = tiles.iter().map(|(_, grid)| grid.orientations()).collect();
+    let edges: Vec<Vec<Edges>> = orientations.iter()
+      .map(|grids| grids.iter().map(edges).collect())
+      .collect();
+
+    let mut index: HashMap<Edge, Vec<usize>> = HashMap::new();
+    for (tile, tile_edges) in edges.iter().enumerate() {
+      for edge in &tile_edges[0] {
+        let sharing = index.entry(canonical(edge)).or_default();
+        if sharing.last() != Some(&tile) {
+          sharing.push(tile);
+        }
+      }
+    }
+
+    Jigsaw { tiles, orientations, edges, index }
+  }
+
+  pub fn id(&self, (tile, _): Placement) -> u32 {
+    self.tiles[tile].0
+  }
+
+  pub fn oriented(&self, (tile, orientation): Placement) -> &Grid<bool> {
+    &self.orientations[tile][orientation]
+  }
+
+  fn is_unmatched(&self, edge: &Edge) -> bool {
+    self.index[&canonical(edge)].len() == 1
+  }
+
+  // How many of a tile's edges no other tile shares: two for a corner, one along the border
+  pub fn unmatched_edges(&self, tile: usize) -> usize {
+    self.edges[tile][0].iter().filter(|edge| self.is_unmatched(edge)).count()
+  }
+
+  // Tiles with two unmatched edges side by side. That includes the ends of a single row of
+  // tiles, which have three, but not the tiles between them, whose two are opposite.
+  pub fn corners(&self) -> Vec<usize> {
+    (0..self.tiles.len())
+      .filter(|&tile| (0..4).any(|side| {
+        let edges = &self.edges[tile][0];
+        self.is_unmatched(&edges[side]) && self.is_unmatched(&edges[(side + 1) % 4])
+      }))
+      .collect()
+  }
+
+  // Tiles row by row, starting from a corner and taking the first tile that fits each place.
+  // The first row ends at the first tile with nothing to its right, which gives the width.
+  // That only goes wrong when edges are ambiguous, and then a full search tries every width.
+  pub fn assemble(&self) -> Result<Grid<Placement>, ImageError> {
+    if self.tiles.is_empty() {
+      return Err(ImageError::NoTiles);
+    }
+    let (placements, width) = self.assemble_greedily()
+      .or_else(|| self.widths().find_map(|width| self.assemble_by_search(width).map(|placements| (placements, width))))
+      .ok_or(ImageError::NoArrangement(self.tiles.len()))?;
+    Ok(to_grid(&placements, width))
+  }
+
+  // Every width the tiles could be laid out in a rectangle with
+  fn widths(&self) -> impl Iterator<Item=usize> {
+    let count = self.tiles.len();
+    (1..=count).filter(move |width| count % width == 0)
+  }
+
+  fn assemble_greedily(&self) -> Option<(Vec<Placement>, usize)> {
+    for corner in self.corners() {
+      for orientation in 0..8 {
+        let edges = &self.edges[corner][orientation];
+        if !self.is_unmatched(&edges[TOP]) || !self.is_unmatched(&edges[LEFT]) {
+          continue;
+        }
+
+        let mut placements = vec![(corner, orientation)];
+        let mut used = vec![false; self.tiles.len()];
+        used[corner] = true;
+
+        // With no width yet, every place is in the first row
+        let mut width = usize::MAX;
+        while placements.len() < self.tiles.len() {
+          if width == usize::MAX && self.is_unmatched(self.edge(placements[placements.len() - 1], RIGHT)) {
+            width = placements.len();
+            if self.tiles.len() % width != 0 {
+              break;
+            }
+          }
+          match self.candidates(&placements, width, &used).first() {
+            Some(&placement) => {
+              used[placement.0] = true;
+              placements.push(placement);
+            },
+            None => break,
+          }
+        }
+        if placements.len() == self.tiles.len() {
+          return Some((placements, width.min(self.tiles.len())));
+        }
+      }
+    }
+    None
+  }
+
+  fn assemble_by_search(&self, width: usize) -> Option<Vec<Placement>> {
+    let mut placements = vec![];
+    let mut used = vec![false; self.tiles.len()];
+    if self.search(&mut placements, width, &mut used) { Some(placements) } else { None }
+  }
+
+  fn search(&self, placements: &mut Vec<Placement>, width: usize, used: &mut Vec<bool>) -> bool {
+    if placements.len() == self.tiles.len() {
+      return true;
+    }
+    for placement in self.candidates(placements, width, used) {
+      used[placement.0] = true;
+      placements.push(placement);
+      if self.search(placements, width, used) {
+        return true;
+      }
+      placements.pop();
+      used[placement.0] = false;
+    }
+    false
+  }
+
+  // Every unused tile and orientation that fits against the tiles left of and above the next place
+  fn candidates(&self, placements: &[Placement], width: usize, used: &[bool]) -> Vec<Placement> {
+    let position = placements.len();
+    let left = if position % width > 0 { Some(self.edge(placements[position - 1], RIGHT)) } else { None };
+    let top = if position >= width { Some(self.edge(placements[position - width], BOTTOM)) } else { None };
+
+    let tiles: Vec<usize> = match left.or(top) {
+      Some(edge) => self.index.get(&canonical(edge)).cloned().unwrap_or_default(),
+      None => (0..self.tiles.len()).collect(),
+    };
+
+    let mut candidates = vec![];
+    for tile in tiles.into_iter().filter(|&tile| !used[tile]) {
+      for (orientation, edges) in self.edges[tile].iter().enumerate() {
+        if left.map_or(true, |s| edges[LEFT] == *s) && top.map_or(true, |s| edges[TOP] == *s) {
+          candidates.push((tile, orientation));
+        }
+      }
+    }
+    candidates
+  }
+
+  fn edge(&self, (tile, orientation): Placement, side: usize) -> &Edge {
+    &self.edges[tile][orientation][side]
+  }
+}
+
+fn to_grid(placements: &[Placement], width: usize) -> Grid<Placement> {
+  Grid::from_fn(width, placements.len() / width, |(x, y)| placements[y * width + x])
+}
+
+fn edges(grid: &Grid<bool>) -> Edges {
+  let (width, height) = (grid.width(), grid.height());
+  [
+    edge((0..width).map(|x| grid[(x, 0)])),
+    edge((0..height).map(|y| grid[(width - 1, y)])),
+    edge((0..width).map(|x| grid[(x, height - 1)])),
+    edge((0..height).map(|y| grid[(0, y)])),
+  ]
+}
+
+fn edge(cells: impl Iterator<Item=bool>) -> Edge {
+  let mut words: Vec<u64> = vec![];
+  let mut length = 0;
+  for cell in cells {
+    if length % 64 == 0 {
+      words.push(0);
+    }
+    let last = words.len() - 1;
+    words[last] = words[last] << 1 | cell as u64;
+    length += 1;
+  }
+  (words.into_boxed_slice(), length)
+}
+
+fn cells((words, length): &Edge) -> impl DoubleEndedIterator<Item=bool> + '_ {
+  (0..*length).map(move |i| {
+    // Bits fill each word from the bottom, so the last word's first bit is lower down
+    let bits_in_word = (length - i / 64 * 64).min(64);
+    words[i / 64] >> (bits_in_word - 1 - i % 64) & 1 == 1
+  })
+}
+
+// The smaller of an edge and its reverse, the same from either tile
+fn canonical(edge: &Edge) -> Edge {
+  let reversed = self::edge(cells(edge).rev());
+  if reversed < *edge { reversed } else { edge.clone() }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::day20::Day20;
+  use crate::helpers::InputSource;
+  use crate::solution::Solution;
+
+  fn example() -> Vec<Tile> {
+    Day20::parse(&InputSource::File("input/test/day20".into())).unwrap()
+  }
+
+  fn corner_ids(jigsaw: &Jigsaw, image: &Grid<Placement>) -> Vec<u32> {
+    let (right, bottom) = (image.width() - 1, image.height() - 1);
+    let mut ids: Vec<u32> = [(0, 0), (right, 0), (0, bottom), (right, bottom)].iter()
+      .map(|&point| jigsaw.id(image[point]))
+      .collect();
+    ids.sort_unstable();
+    ids
+  }
+
+  #[test]
+  fn finds_corners_from_unmatched_edges() {
+    let tiles = example();
+    let jigsaw = Jigsaw::new(&tiles);
+    let mut corners: Vec<u32> = jigsaw.corners().into_iter().map(|tile| tiles[tile].0).collect();
+    corners.sort_unstable();
+    assert_eq!(corners, vec![1171, 1951, 2971, 3079]);
+    assert_eq!((0..tiles.len()).filter(|&tile| jigsaw.unmatched_edges(tile) == 0).count(), 1);
+  }
+
+  #[test]
+  fn search_agrees_with_greedy_assembly() {
+    let tiles = example();
+    let jigsaw = Jigsaw::new(&tiles);
+    let greedy = jigsaw.assemble().unwrap();
+    let searched = to_grid(&jigsaw.assemble_by_search(3).unwrap(), 3);
+    assert_eq!((greedy.width(), greedy.height()), (3, 3));
+    assert_eq!(corner_ids(&jigsaw, &greedy), vec![1171, 1951, 2971, 3079]);
+    assert_eq!(corner_ids(&jigsaw, &searched), corner_ids(&jigsaw, &greedy));
+    assert_eq!(jigsaw.widths().collect::<Vec<_>>(), vec![1, 3, 9]);
+    assert_eq!(jigsaw.assemble_by_search(9), None);
+  }
+  #[test]
+  fn reads_edges_of_any_length() {
+    let bits: Vec<bool> = (0..130).map(|i| i % 3 == 0 || i % 7 == 0).collect();
+    let forwards = edge(bits.iter().copied());
+    let backwards = edge(bits.iter().rev().copied());
+    assert_eq!((forwards.0.len(), forwards.1), (3, 130));
+    assert_eq!(cells(&forwards).collect::<Vec<_>>(), bits);
+    assert_ne!(forwards, backwards);
+    assert_eq!(canonical(&forwards), canonical(&backwards));
+  }
+}
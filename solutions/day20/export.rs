@@ -0,0 +1,119 @@
+use crate::helpers::Grid;
+use std::io::{self, Write};
+
+// (set, part of a tile border, part of a sea monster)
+pub type Pixel = (bool, bool, bool);
+
+type Colour = (u8, u8, u8);
+
+// Plain netpbm lines should be at most this long
+const LINE_LENGTH: usize = 70;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ImageFormat {
+  // Black and white
+  Pbm,
+  // Grey levels, with tile borders paler than the picture
+  Pgm,
+  // Colour, with sea monsters in red as well
+  Ppm,
+}
+
+impl ImageFormat {
+  pub fn from_name(name: &str) -> Option<ImageFormat> {
+    match name {
+      "pbm" => Some(ImageFormat::Pbm),
+      "pgm" => Some(ImageFormat::Pgm),
+      "ppm" => Some(ImageFormat::Ppm),
+      _ => None,
+    }
+  }
+}
+
+pub fn write_image(pixels: &Grid<Pixel>, format: ImageFormat, mut output: impl Write) -> io::Result<()> {
+  let magic = match format {
+    ImageFormat::Pbm => "P1",
+    ImageFormat::Pgm => "P2",
+    ImageFormat::Ppm => "P3",
+  };
+  writeln!(output, "{}", magic)?;
+  writeln!(output, "{} {}", pixels.width(), pixels.height())?;
+  if format != ImageFormat::Pbm {
+    writeln!(output, "255")?;
+  }
+
+  for row in pixels.rows() {
+    let values = row.iter().map(|&pixel| match format {
+      ImageFormat::Pbm => (if pixel.0 { "1" } else { "0" }).to_string(),
+      ImageFormat::Pgm => shade(pixel).to_string(),
+      ImageFormat::Ppm => {
+        let (r, g, b) = colour(pixel);
+        format!("{} {} {}", r, g, b)
+      },
+    });
+    write_row(&mut output, values)?;
+  }
+  Ok(())
+}
+
+// Each row starts on a new line and wraps before getting too long
+fn write_row(output: &mut impl Write, values: impl Iterator<Item=String>) -> io::Result<()> {
+  let mut line = String::new();
+  for value in values {
+    if !line.is_empty() && line.len() + 1 + value.len() > LINE_LENGTH {
+      writeln!(output, "{}", line)?;
+      line.clear();
+    }
+    if !line.is_empty() {
+      line.push(' ');
+    }
+    line.push_str(&value);
+  }
+  writeln!(output, "{}", line)
+}
+
+fn shade((set, border, _): Pixel) -> u8 {
+  match (set, border) {
+    (true, false) => 0,
+    (false, false) => 255,
+    (true, true) => 128,
+    (false, true) => 208,
+  }
+}
+
+fn colour(pixel: Pixel) -> Colour {
+  match pixel {
+    (true, _, true) => (220, 20, 60),
+    _ => {
+      let grey = shade(pixel);
+      (grey, grey, grey)
+    },
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn export(pixels: &Grid<Pixel>, format: ImageFormat) -> String {
+    let mut output = vec![];
+    write_image(pixels, format, &mut output).unwrap();
+    String::from_utf8(output).unwrap()
+  }
+
+  #[test]
+  fn writes_plain_netpbm() {
+    let pixels = Grid::from_rows(vec![
+      vec![(true, false, false), (false, false, false)],
+      vec![(true, true, false), (true, false, true)],
+    ]).unwrap();
+    assert_eq!(export(&pixels, ImageFormat::Pbm), "P1\n2 2\n1 0\n1 1\n");
+    assert_eq!(export(&pixels, ImageFormat::Pgm), "P2\n2 2\n255\n0 255\n128 0\n");
+    assert_eq!(export(&pixels, ImageFormat::Ppm), "P3\n2 2\n255\n0 0 0 255 255 255\n128 128 128 220 20 60\n");
+
+    let wide = Grid::filled(40, 1, (false, false, false));
+    let pgm = export(&wide, ImageFormat::Pgm);
+    assert!(pgm.lines().all(|line| line.len() <= LINE_LENGTH));
+    assert_eq!(pgm.split_whitespace().skip(4).count(), 40);
+  }
+}
@@ -0,0 +1,91 @@
+use crate::helpers::Grid;
+
+// A shape to look for, named after the file it came from; only its set cells have to match
+pub type Pattern = (String, Grid<bool>);
+
+// (pattern index, orientation from Grid::orientations, top left corner in the image)
+pub type PatternMatch = (usize, usize, (usize, usize));
+
+// Every place any of the patterns appears, in any orientation. Orientations that turn a
+// symmetric pattern into the same shape again are only searched once.
+pub fn find_matches(image: &Grid<bool>, patterns: &[Pattern]) -> Vec<PatternMatch> {
+  let mut matches = vec![];
+  for (index, (_, pattern)) in patterns.iter().enumerate() {
+    let mut seen: Vec<Grid<bool>> = vec![];
+    for (orientation, oriented) in pattern.orientations().into_iter().enumerate() {
+      if seen.contains(&oriented) {
+        continue;
+      }
+      for point in image.points().filter(|&point| fits(image, &oriented, point)) {
+        matches.push((index, orientation, point));
+      }
+      seen.push(oriented);
+    }
+  }
+  matches
+}
+
+fn fits(image: &Grid<bool>, pattern: &Grid<bool>, (x, y): (usize, usize)) -> bool {
+  if x + pattern.width() > image.width() || y + pattern.height() > image.height() {
+    return false;
+  }
+
+  pattern.points().all(|(i, j)| !pattern[(i, j)] || image[(x + i, y + j)])
+}
+
+// The cells some match covers; overlapping matches share cells rather than counting them twice
+pub fn coverage(image: &Grid<bool>, patterns: &[Pattern], matches: &[PatternMatch]) -> Grid<bool> {
+  let mut mask = Grid::filled(image.width(), image.height(), false);
+  for &(index, orientation, (x, y)) in matches {
+    let oriented = &patterns[index].1.orientations()[orientation];
+    for (i, j) in oriented.points().filter(|&point| oriented[point]) {
+      mask[(x + i, y + j)] = true;
+    }
+  }
+  mask
+}
+
+// Set cells that no match covers
+pub fn roughness(image: &Grid<bool>, mask: &Grid<bool>) -> usize {
+  image.points().filter(|&point| image[point] && !mask[point]).count()
+}
+
+pub fn describe_orientation(orientation: usize) -> String {
+  let degrees = orientation / 2 * 90;
+  match (degrees, orientation % 2 == 1) {
+    (0, false) => "as drawn".to_string(),
+    (0, true) => "mirrored".to_string(),
+    (_, false) => format!("turned {} degrees", degrees),
+    (_, true) => format!("turned {} degrees and mirrored", degrees),
+  }
+}
+
+pub fn format_match(patterns: &[Pattern], &(index, orientation, (x, y)): &PatternMatch) -> String {
+  format!("{} {} at ({}, {})", patterns[index].0, describe_orientation(orientation), x, y)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::error::SourceLine;
+
+  fn grid(text: &str) -> Grid<bool> {
+    let lines: Vec<String> = text.lines().map(String::from).collect();
+    Grid::parse(SourceLine::numbered(20, &lines)).unwrap()
+  }
+
+  #[test]
+  fn counts_overlapping_matches_once() {
+    let image = grid("###.\n.##.\n....");
+    let patterns = vec![("bar".to_string(), grid("##")), ("dot".to_string(), grid("#"))];
+    let matches = find_matches(&image, &patterns[..1]);
+    // Two across the top row, one in the second, and the two downwards after turning
+    assert_eq!(matches.len(), 5);
+    assert!(matches.contains(&(0, 2, (1, 0))));
+    assert_eq!(roughness(&image, &coverage(&image, &patterns, &matches)), 0);
+
+    // A single cell looks the same every way round
+    assert_eq!(find_matches(&image, &patterns[1..]).len(), 5);
+    assert_eq!(format_match(&patterns, &matches[4]), "bar turned 90 degrees at (2, 0)");
+  }
+}
@@ -1,23 +1,33 @@
-use crate::helpers;
-use std::error::Error;
+use crate::helpers::{self, InputSource};
+use crate::solution::{Answer, Solution};
+use crate::error::{Error, SourceLine};
 use std::collections::HashMap;
 
-const INPUT_FILE: &str = "input/day21";
+pub const INPUT_FILE: &str = "input/day21";
+
+const DAY: u8 = 21;
 
 type ParsedMenu = Vec<(Vec<String>, Vec<String>)>;
 
-pub fn solve() -> Result<(), Box<dyn Error>> {
-  let menu_items = parse_input()?;
-  solve_part_one(&menu_items);
-  /*
-  let test_border = border(&(&tiles[0], Direction::N, false), Direction::E);
-  let s = test_border.iter().map(|&p| if p {'#'} else {'.'}).collect::<String>();
-  println!("Test {}", s);
-  */
-  return Ok(());
+pub struct Day21;
+
+impl Solution for Day21 {
+  type Input = ParsedMenu;
+
+  fn parse(input: &InputSource) -> Result<Self::Input, Error> {
+    parse_input(input)
+  }
+
+  fn part_one(menu_items: &Self::Input) -> Result<Answer, Error> {
+    Ok(solve_part_one(menu_items))
+  }
+
+  fn part_two(menu_items: &Self::Input) -> Result<Answer, Error> {
+    Ok(solve_part_two(menu_items))
+  }
 }
 
-fn solve_part_one(menu: &ParsedMenu) {
+fn find_allergen_ingredients(menu: &ParsedMenu) -> HashMap<String, String> {
   let mut ingredients_by_allergen: HashMap<String, Vec<String>> = HashMap::new();
 
   for (ingredients, allergens) in menu {
@@ -39,7 +49,7 @@ fn solve_part_one(menu: &ParsedMenu) {
       if let Some(ings) = existing_ingredients {
         ings.retain(|ing| ingredients.contains(ing));
       } else {
-        ingredients_by_allergen.insert(allergen.clone(), ingredients.iter().map(|ing| ing.clone()).collect());
+        ingredients_by_allergen.insert(allergen.clone(), ingredients.to_vec());
       }
     }
   }
@@ -51,52 +61,75 @@ fn solve_part_one(menu: &ParsedMenu) {
 
     for (alg, ings) in ingredients_by_allergen.iter_mut() {
       if ings.len() == 1 {
-        if solved_ingredients.get(ings[0].as_str()).is_none() {
+        if !solved_ingredients.contains_key(ings[0].as_str()) {
           solved_ingredients.insert(ings[0].clone(), alg.clone());
           changed = true;
         }
       } else {
         let prev_len = ings.len();
-        ings.retain(|ing| solved_ingredients.get(ing).is_none());
+        ings.retain(|ing| !solved_ingredients.contains_key(ing));
         changed = changed || prev_len != ings.len();
       }
     }
   }
 
+  solved_ingredients
+}
+
+fn solve_part_one(menu: &ParsedMenu) -> Answer {
+  let solved_ingredients = find_allergen_ingredients(menu);
   let mut count = 0;
   for (ingredients, _) in menu {
     for ingredient in ingredients {
-      if solved_ingredients.get(ingredient).is_none() {
+      if !solved_ingredients.contains_key(ingredient) {
         count += 1;
       }
     }
   }
 
-  println!("Part 1: {}", count);
+  count.into()
+}
 
+fn solve_part_two(menu: &ParsedMenu) -> Answer {
+  let solved_ingredients = find_allergen_ingredients(menu);
   let mut dangerous_ingredients: Vec<(String, String)> = solved_ingredients
     .iter()
     .map(|(ing, alg)| (ing.clone(), alg.clone()))
     .collect();
   dangerous_ingredients.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
   let list = dangerous_ingredients.iter().map(|(ing, _)| ing.clone()).collect::<Vec<_>>().join(",");
-  println!("Part 2: {}", list);
+  list.into()
 }
 
-fn parse_input() -> Result<Vec<(Vec<String>, Vec<String>)>, Box<dyn Error>> {
-  let lines = helpers::read_lines(INPUT_FILE)?.map(|l| l.unwrap());
+fn parse_input(input: &InputSource) -> Result<ParsedMenu, Error> {
+  let lines = helpers::read_lines(input)?;
 
   let mut result = vec![];
-  for line in lines {
+  for (i, line) in lines.enumerate() {
+    let line = line?;
+    let source = SourceLine::new(DAY, i + 1, &line);
     let mut parts = line.split(" (contains ");
-    let ingredients_part = parts.next().unwrap();
-    let allergens_part = parts.next().unwrap();
+    let ingredients_part = source.expect(parts.next(), "expected ingredients")?;
+    let allergens_part = source.expect(parts.next(), "expected ' (contains '")?;
+    let allergens_part = source.expect(allergens_part.strip_suffix(')'), "expected ')'")?;
 
-    let ingredients: Vec<String> = ingredients_part.split_whitespace().map(|s| String::from(s)).collect();
-    let allergens: Vec<String> = allergens_part[..allergens_part.len()-1].split(", ").map(|s| String::from(s)).collect();
+    let ingredients: Vec<String> = ingredients_part.split_whitespace().map(String::from).collect();
+    let allergens: Vec<String> = allergens_part.split(", ").map(String::from).collect();
 
     result.push((ingredients, allergens));
   }
 
   Ok(result)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn solves_example() {
+    let input = Day21::parse(&InputSource::File("input/test/day21".into())).unwrap();
+    assert_eq!(Day21::part_one(&input).unwrap(), Answer::Int(5));
+    assert_eq!(Day21::part_two(&input).unwrap(), Answer::Str(String::from("mxmxvkd,sqjhc,fvjkl")));
+  }
+}
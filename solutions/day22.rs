@@ -1,51 +1,56 @@
-use crate::helpers;
-use std::error::Error;
+use crate::helpers::{self, InputSource};
+use crate::solution::{Answer, Solution};
+use crate::error::Error;
 use std::collections::VecDeque;
 use std::collections::HashSet;
 
-const INPUT_FILE: &str = "input/day22";
+pub const INPUT_FILE: &str = "input/day22";
 
 type GameState = (VecDeque<u32>, VecDeque<u32>);
 
-pub fn solve() -> Result<(), Box<dyn Error>> {
-  let mut initial_state = parse_input()?;
-  solve_part_one(&mut initial_state);
-  let mut initial_state = parse_input()?;
-  solve_part_two(&mut initial_state);
-
-  /*
-  let test_border = border(&(&tiles[0], Direction::N, false), Direction::E);
-  let s = test_border.iter().map(|&p| if p {'#'} else {'.'}).collect::<String>();
-  println!("Test {}", s);
-  */
-  return Ok(());
+pub struct Day22;
+
+impl Solution for Day22 {
+  type Input = GameState;
+
+  fn parse(input: &InputSource) -> Result<Self::Input, Error> {
+    parse_input(input)
+  }
+
+  fn part_one(initial_state: &Self::Input) -> Result<Answer, Error> {
+    Ok(solve_part_one(&mut initial_state.clone()))
+  }
+
+  fn part_two(initial_state: &Self::Input) -> Result<Answer, Error> {
+    Ok(solve_part_two(&mut initial_state.clone()))
+  }
 }
 
-fn solve_part_one(state: &mut GameState) {
+fn solve_part_one(state: &mut GameState) -> Answer {
 
-  while state.0.len() > 0 && state.1.len() > 0 {
+  while !state.0.is_empty() && !state.1.is_empty() {
     run_one_round(state);
   }
 
-  let score = if state.0.len() == 0 {
+  let score = if state.0.is_empty() {
     calculate_score(&state.1)
   } else {
     calculate_score(&state.0)
   };
 
-  println!("Part 1: {}", score);
+  score.into()
 }
 
-fn solve_part_two(state: &mut GameState) {
+fn solve_part_two(state: &mut GameState) -> Answer {
   run_recursive_game(state);
 
-  let score = if state.0.len() == 0 {
+  let score = if state.0.is_empty() {
     calculate_score(&state.1)
   } else {
     calculate_score(&state.0)
   };
 
-  println!("Part 2: {}", score);
+  score.into()
 }
 
 fn calculate_score(final_deck: &VecDeque<u32>) -> u64 {
@@ -70,8 +75,8 @@ fn run_recursive_game(state: &mut GameState) -> bool {
   let mut state_hashes = HashSet::new();
 
   loop {
-    if state.0.len() == 0 {return false};
-    if state.1.len() == 0 {return true};
+    if state.0.is_empty() {return false};
+    if state.1.is_empty() {return true};
     let state_hash = get_state_hash(state);
     if state_hashes.contains(&state_hash) {
       return true;
@@ -119,7 +124,7 @@ fn get_state_hash(state: &GameState) -> String {
   for val in p1 {
     hash.push_str(format!("{},", val).as_str());
   }
-  hash.push_str("-");
+  hash.push('-');
   for val in p2 {
     hash.push_str(format!("{},", val).as_str());
   }
@@ -136,28 +141,43 @@ fn copy_front(list: &VecDeque<u32>, n: u32) -> VecDeque<u32> {
   result
 }
 
-fn parse_input() -> Result<GameState, Box<dyn Error>> {
-  let mut lines = helpers::read_lines(INPUT_FILE)?.map(|l| l.unwrap());
+fn parse_input(input: &InputSource) -> Result<GameState, Error> {
+  let lines = helpers::read_lines(input)?.collect::<Result<Vec<_>, _>>()?;
 
   let mut player1 = VecDeque::new();
   let mut player2 = VecDeque::new();
 
   let mut player1_done = false;
 
-  while let Some(l) = lines.next() {
-    if l.trim().len() == 0 {
+  for l in lines {
+    if l.trim().is_empty() {
       player1_done = true;
     } else {
-      match l.parse::<u32>() {
-        Ok(val) => if !player1_done {
-          player1.push_back(val)
-        } else {
-          player2.push_back(val)
-        }
-        Err(_) => ()
-      }
+      if let Ok(val) = l.parse::<u32>() { if !player1_done {
+        player1.push_back(val)
+      } else {
+        player2.push_back(val)
+      } }
     }
   }
 
   Ok((player1, player2))
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn solves_example() {
+    let input = Day22::parse(&InputSource::File("input/test/day22".into())).unwrap();
+    assert_eq!(Day22::part_one(&input).unwrap(), Answer::Int(306));
+    assert_eq!(Day22::part_two(&input).unwrap(), Answer::Int(291));
+  }
+
+  #[test]
+  fn stops_repeated_recursive_game() {
+    let input = Day22::parse(&InputSource::File("input/test/day22_inf".into())).unwrap();
+    assert_eq!(Day22::part_two(&input).unwrap(), Answer::Int(105));
+  }
+}
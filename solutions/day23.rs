@@ -1,29 +1,42 @@
-use crate::helpers;
-use std::error::Error;
+use crate::helpers::{self, InputSource};
+use crate::solution::{Answer, Solution};
+use crate::error::{Error, ParseError, SourceLine};
 use std::collections::VecDeque;
 use std::collections::HashMap;
 
-const INPUT_FILE: &str = "input/day23";
+pub const INPUT_FILE: &str = "input/day23";
+
+const DAY: u8 = 23;
 
 const MILLION: i64 = 1000000;
 
 type Cups = HashMap<i64, i64>;
 
-pub fn solve() -> Result<(), Box<dyn Error>> {
-  let mut initial_state = parse_input()?;
-  solve_part_one(&mut initial_state);
-  let mut initial_state = parse_input()?;
-  solve_part_two(&mut initial_state);
-  return Ok(());
+pub struct Day23;
+
+impl Solution for Day23 {
+  type Input = VecDeque<i64>;
+
+  fn parse(input: &InputSource) -> Result<Self::Input, Error> {
+    parse_input(input)
+  }
+
+  fn part_one(initial_state: &Self::Input) -> Result<Answer, Error> {
+    Ok(solve_part_one(&mut initial_state.clone()))
+  }
+
+  fn part_two(initial_state: &Self::Input) -> Result<Answer, Error> {
+    Ok(solve_part_two(&mut initial_state.clone()))
+  }
 }
 
-fn solve_part_one(cups: &mut VecDeque<i64>) {
+fn solve_part_one(cups: &mut VecDeque<i64>) -> Answer {
   let mut cups2: Cups = HashMap::new();
   let first_val = cups[0];
   let mut prev_val = cups[0];
-  for i in 1..cups.len() {
-    cups2.insert(prev_val, cups[i]);
-    prev_val = cups[i];
+  for &cup in cups.iter().skip(1) {
+    cups2.insert(prev_val, cup);
+    prev_val = cup;
   }
   cups2.insert(prev_val, first_val);
 
@@ -34,10 +47,10 @@ fn solve_part_one(cups: &mut VecDeque<i64>) {
     val = *cups2.get(&val).unwrap()
   }
 
-  println!("Part 1: {}", get_order_after_val(&cups2, 1));
+  get_order_after_val(&cups2, 1).into()
 }
 
-fn solve_part_two(cups: &mut VecDeque<i64>) {
+fn solve_part_two(cups: &mut VecDeque<i64>) -> Answer {
   let mut cups2: Cups = HashMap::new();
   let first_val = cups[0];
   let mut prev_val = cups[0];
@@ -54,10 +67,7 @@ fn solve_part_two(cups: &mut VecDeque<i64>) {
   cups2.insert(prev_val, first_val);
 
   let mut val = first_val;
-  for i in 0..10*MILLION {
-    if i % 10000 == 0 {
-      println!("Round {}", i);
-    }
+  for _ in 0..10*MILLION {
     move_cups2(&mut cups2, val);
     val = *cups2.get(&val).unwrap()
   }
@@ -65,13 +75,7 @@ fn solve_part_two(cups: &mut VecDeque<i64>) {
   let &first = cups2.get(&1).unwrap();
   let &second = cups2.get(&first).unwrap();
 
-  println!("Answer {}", first * second);
-}
-
-fn print(cups: &Cups) {
-  for i in 1..=cups.len() {
-    println!("{} -> {:?}", i, cups.get(&(i as i64)))
-  }
+  (first * second).into()
 }
 
 fn get_order_after_val(cups: &Cups, v: i64) -> String {
@@ -88,58 +92,14 @@ fn get_order_after_val(cups: &Cups, v: i64) -> String {
   nums.iter().map(|v| v.to_string()).collect::<String>()
 }
 
-fn get_labeling(cups: &VecDeque<i64>) -> String {
-  cups.iter().map(|v| v.to_string()).collect::<String>()
-}
-
-fn get_order_after_1(cups: &VecDeque<i64>) -> String {
-  let index_of_1 = cups.iter().position(|&c| c == 1).unwrap();
-  let mut vals = vec![];
-  for i in index_of_1+1..cups.len() {
-    vals.push(cups[i].to_string());
-  }
-  for i in 0..index_of_1 {
-    vals.push(cups[i].to_string());
-  }
-
-  vals.into_iter().collect::<String>()
-}
-
-fn move_cups(cups: &mut VecDeque<i64>, curr: usize, num: usize) {
-  let num_cups = cups.len();
-  let curr_val = cups[curr];
-  let mut moved_cups = vec![];
-  for _ in curr+1..=curr + num {
-    let index_to_remove = if curr >= cups.len()-1 {0} else {curr+1};
-    moved_cups.push(cups.remove(index_to_remove).unwrap());
-  }
-  let mut target_val = (curr_val - 2).rem_euclid(num_cups as i64) + 1;
-  while moved_cups.contains(&target_val) {
-    target_val = (target_val - 2).rem_euclid(num_cups as i64) + 1;
-  }
-
-  let target = cups.iter().position(|&v| v == target_val).unwrap();
-  for c in moved_cups.iter().rev() {
-    cups.insert((target + 1).rem_euclid(num_cups), *c);
-  }
-/*
-  //println!("{}, {}, {}", curr, curr_val, cups[curr]);
-  while cups[curr] != curr_val {
-    //println!("rotating {}", get_labeling(cups));
-    cups.rotate_right(1);
-    //println!("rotated {}", get_labeling(cups));
-  }
-  */
-}
-
 fn move_cups2(cups: &mut Cups, curr_val: i64) {
   // Move forward 3, find value
-  let mut moved_vals = vec![0,0,0];
+  let mut moved_vals = [0,0,0];
   let mut last_moved = curr_val;
   let mut target = (curr_val - 2).rem_euclid(cups.len() as i64) + 1;
-  for i in 0..3 {
+  for moved_val in moved_vals.iter_mut() {
     last_moved = *cups.get(&last_moved).unwrap();
-    moved_vals[i] = last_moved;
+    *moved_val = last_moved;
   }
 
   while moved_vals.contains(&target) {
@@ -154,11 +114,48 @@ fn move_cups2(cups: &mut Cups, curr_val: i64) {
 }
 
 
-fn parse_input() -> Result<VecDeque<i64>, Box<dyn Error>> {
-  let mut lines = helpers::read_lines(INPUT_FILE)?.map(|l| l.unwrap());
-  let line = lines.next().unwrap();
+fn parse_input(input: &InputSource) -> Result<VecDeque<i64>, Error> {
+  let mut lines = helpers::read_lines(input)?;
+  let line = lines.next().ok_or_else(|| ParseError::missing_line(DAY, 1, "expected cup labels"))??;
+  let source = SourceLine::new(DAY, 1, &line);
 
-  let numbers: VecDeque<i64> = line.chars().map(|c| c.to_digit(10).unwrap() as i64).collect();
+  let mut numbers = VecDeque::new();
+  for (i, c) in line.char_indices() {
+    match c.to_digit(10) {
+      Some(n) => numbers.push_back(n as i64),
+      None => return Err(source.error(&line[i..], "expected a digit").into()),
+    }
+  }
+  if numbers.is_empty() {
+    return Err(source.error(&line, "expected cup labels").into());
+  }
 
   Ok(numbers)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn solves_example() {
+    let input = Day23::parse(&InputSource::File("input/test/day23".into())).unwrap();
+    assert_eq!(Day23::part_one(&input).unwrap(), Answer::Str(String::from("67384529")));
+    assert_eq!(Day23::part_two(&input).unwrap(), Answer::Int(149245887792));
+  }
+
+  #[test]
+  fn solves_second_example() {
+    let input = Day23::parse(&InputSource::File("input/test/day23_test".into())).unwrap();
+    assert_eq!(Day23::part_one(&input).unwrap(), Answer::Str(String::from("65739248")));
+    assert_eq!(Day23::part_two(&input).unwrap(), Answer::Int(341547118850));
+  }
+
+  #[test]
+  fn rejects_an_empty_cup_list() {
+    match Day23::parse(&InputSource::Text("\n".into())) {
+      Err(Error::Parse(e)) => assert_eq!((e.line, e.message.as_str()), (1, "expected cup labels")),
+      _ => panic!("expected a parse error"),
+    }
+  }
+}
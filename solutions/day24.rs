@@ -1,10 +1,16 @@
-use crate::helpers;
-use std::error::Error;
+use crate::helpers::{self, InputSource};
+use crate::helpers::automaton::{Automaton, Neighbourhood, Rules};
+use crate::solution::{Answer, Solution};
+use crate::error::{Error, SourceLine};
 use std::collections::HashSet;
 
-const INPUT_FILE: &str = "input/day24";
+pub const INPUT_FILE: &str = "input/day24";
 
-enum Direction {
+const DAY: u8 = 24;
+
+const DAYS: usize = 100;
+
+pub enum Direction {
   E,
   NW,
   NE,
@@ -15,102 +21,94 @@ enum Direction {
 
 type HexCoord = (i64, i64);
 
-fn hex_move(&(x, y): &HexCoord, dir: &Direction) -> HexCoord {
-  match dir {
-    Direction::E => (x + 1, y),
-    Direction::NE => (x, y + 1),
-    Direction::NW => (x - 1, y + 1),
-    Direction::W => (x - 1, y),
-    Direction::SW => (x, y - 1),
-    Direction::SE => (x + 1, y - 1)
+// Axial coordinates: x grows to the east and y to the north-east
+const HEX_DIRECTIONS: [HexCoord; 6] = [(1, 0), (0, 1), (-1, 1), (-1, 0), (0, -1), (1, -1)];
+
+impl Direction {
+  fn offset(&self) -> HexCoord {
+    match self {
+      Direction::E => HEX_DIRECTIONS[0],
+      Direction::NE => HEX_DIRECTIONS[1],
+      Direction::NW => HEX_DIRECTIONS[2],
+      Direction::W => HEX_DIRECTIONS[3],
+      Direction::SW => HEX_DIRECTIONS[4],
+      Direction::SE => HEX_DIRECTIONS[5],
+    }
   }
 }
 
-pub fn solve() -> Result<(), Box<dyn Error>> {
-  let tile_directions = parse_input()?;
-  let mut flipped_tiles = solve_part_one(&tile_directions);
-  solve_part_two(&mut flipped_tiles);
-  return Ok(());
+fn hex_move(&(x, y): &HexCoord, dir: &Direction) -> HexCoord {
+  let (dx, dy) = dir.offset();
+  (x + dx, y + dy)
 }
 
-fn solve_part_one(tile_directions: &Vec<Vec<Direction>>) -> HashSet<(i64, i64)> {
-  let mut flipped_tiles = HashSet::new();
+pub struct Day24;
 
-  for directions in tile_directions {
-    let mut tile: HexCoord = (0,0);
-    for dir in directions {
-      tile = hex_move(&tile, dir);
-    }
+impl Solution for Day24 {
+  type Input = Vec<Vec<Direction>>;
 
-    if flipped_tiles.get(&tile).is_none() {
-      flipped_tiles.insert(tile);
-    } else {
-      flipped_tiles.remove(&tile);
-    }
+  fn parse(input: &InputSource) -> Result<Self::Input, Error> {
+    parse_input(input)
   }
-  println!("Part 1: {}, {:?}", flipped_tiles.len(), flipped_tiles);
 
-  flipped_tiles
-}
+  fn part_one(tile_directions: &Self::Input) -> Result<Answer, Error> {
+    Ok(solve_part_one(tile_directions))
+  }
 
-fn solve_part_two(flipped_tiles: &mut HashSet<(i64, i64)>) {
-  for _ in 0..100 {
-    get_tiles_next_day(flipped_tiles);
+  fn part_two(tile_directions: &Self::Input) -> Result<Answer, Error> {
+    Ok(solve_part_two(tile_directions))
   }
+}
 
-  println!("Part 2: {}", flipped_tiles.len());
+fn solve_part_one(tile_directions: &Vec<Vec<Direction>>) -> Answer {
+  flip_tiles(tile_directions).len().into()
 }
 
-fn get_tiles_next_day(flipped_tiles: &mut HashSet<(i64, i64)>) {
-  let mut tiles_to_flip = HashSet::new();
-  let dirs = [Direction::E, Direction::NE, Direction::NW, Direction::W, Direction::SW, Direction::SE];
+fn solve_part_two(tile_directions: &Vec<Vec<Direction>>) -> Answer {
+  let black_tiles = flip_tiles(tile_directions).into_iter().map(|(x, y)| vec![x, y]);
+  let mut floor = Automaton::new(&rules(), black_tiles);
+  floor.run(DAYS);
 
-  for flipped_tile in flipped_tiles.iter() {
-    let blacks = get_black_neighbours(flipped_tiles, flipped_tile);
-    if blacks == 0 || blacks > 2 {
-      tiles_to_flip.insert(*flipped_tile);
-    }
-    for dir in dirs.iter() {
-      let neighbour = hex_move(flipped_tile, dir);
-      if flipped_tiles.get(&neighbour).is_none() {
-        let blacks = get_black_neighbours(flipped_tiles, &neighbour);
-        if blacks == 2 {
-          tiles_to_flip.insert(neighbour);
-        }
-      }
-    }
-  }
+  floor.active().len().into()
+}
 
-  for tile in tiles_to_flip {
-    if flipped_tiles.get(&tile).is_none() {
-      flipped_tiles.insert(tile);
-    } else {
-      flipped_tiles.remove(&tile);
-    }
+// Black tiles are the active cells
+pub fn rules() -> Rules {
+  Rules {
+    neighbourhood: Neighbourhood::Offsets(HEX_DIRECTIONS.iter().map(|&(x, y)| vec![x, y]).collect()),
+    birth: vec![2],
+    survival: vec![1, 2],
   }
 }
 
-fn get_black_neighbours(flipped_tiles: &HashSet<(i64, i64)>, coord: &HexCoord) -> i32 {
-  let dirs = [Direction::E, Direction::NE, Direction::NW, Direction::W, Direction::SW, Direction::SE];
+fn flip_tiles(tile_directions: &Vec<Vec<Direction>>) -> HashSet<(i64, i64)> {
+  let mut flipped_tiles = HashSet::new();
 
-  let mut count_black = 0;
-  for dir in dirs.iter() {
-    let neighbour = hex_move(coord, dir);
-    if flipped_tiles.get(&neighbour).is_some() {
-      count_black += 1;
+  for directions in tile_directions {
+    let mut tile: HexCoord = (0,0);
+    for dir in directions {
+      tile = hex_move(&tile, dir);
+    }
+
+    if !flipped_tiles.contains(&tile) {
+      flipped_tiles.insert(tile);
+    } else {
+      flipped_tiles.remove(&tile);
     }
   }
 
-  count_black
+  flipped_tiles
 }
 
-fn parse_input() -> Result<Vec<Vec<Direction>>, Box<dyn Error>> {
+fn parse_input(input: &InputSource) -> Result<Vec<Vec<Direction>>, Error> {
   let mut result = vec![];
-  let lines = helpers::read_lines(INPUT_FILE)?.map(|l| l.unwrap());
-  for line in lines {
+  let lines = helpers::read_lines(input)?;
+  for (i, line) in lines.enumerate() {
+    let line = line?;
+    let source = SourceLine::new(DAY, i + 1, &line);
     let mut directions = vec![];
     let mut prev_char = None;
-    for c in line.chars() {
+    for (j, c) in line.char_indices() {
       match c {
         'n' => prev_char = Some('n'),
         's' => prev_char = Some('s'),
@@ -130,10 +128,29 @@ fn parse_input() -> Result<Vec<Vec<Direction>>, Box<dyn Error>> {
           };
           prev_char = None;
         }
-        _ => ()
+        _ => return Err(source.error(&line[j..], "expected n, s, e or w").into()),
       }
     }
     result.push(directions);
   }
   Ok(result)
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn solves_example() {
+    let input = Day24::parse(&InputSource::File("input/test/day24".into())).unwrap();
+    assert_eq!(Day24::part_one(&input).unwrap(), Answer::Int(10));
+    assert_eq!(Day24::part_two(&input).unwrap(), Answer::Int(2208));
+  }
+
+  #[test]
+  fn solves_example_2() {
+    let input = Day24::parse(&InputSource::File("input/test/day24_2".into())).unwrap();
+    assert_eq!(Day24::part_one(&input).unwrap(), Answer::Int(3));
+    assert_eq!(Day24::part_two(&input).unwrap(), Answer::Int(2150));
+  }
+}
@@ -1,27 +1,39 @@
-use crate::helpers;
-use std::error::Error;
+use crate::helpers::{self, InputSource};
+use crate::solution::{Answer, Solution};
+use crate::error::{Error, ParseError, SourceLine};
 
-const INPUT_FILE: &str = "input/day25";
+pub const INPUT_FILE: &str = "input/day25";
+
+const DAY: u8 = 25;
 
 const MODULUS: u64 = 20201227;
 const SUBJECT: u64 = 7;
 
-pub fn solve() -> Result<(), Box<dyn Error>> {
-  let public_keys = parse_input()?;
-  solve_part_one(&public_keys);
-  return Ok(());
+pub struct Day25;
+
+impl Solution for Day25 {
+  type Input = (u64, u64);
+
+  fn parse(input: &InputSource) -> Result<Self::Input, Error> {
+    parse_input(input)
+  }
+
+  fn part_one(public_keys: &Self::Input) -> Result<Answer, Error> {
+    Ok(solve_part_one(public_keys))
+  }
+
+  fn part_two(_public_keys: &Self::Input) -> Result<Answer, Error> {
+    Ok(Answer::None)
+  }
 }
 
-fn solve_part_one(public_keys: &(u64, u64)) {
+fn solve_part_one(public_keys: &(u64, u64)) -> Answer {
 
   let &(card_key, door_key) = public_keys;
 
   let card_loop = find_loop_size(card_key);
-  let door_loop = find_loop_size(door_key);
 
-  let card_enc_key = transform(1, door_key, card_loop);
-
-  println!("{} {}", card_enc_key, card_loop);
+  transform(1, door_key, card_loop).into()
 }
 
 fn transform (base: u64, subject: u64, n: u64) -> u64 {
@@ -43,11 +55,35 @@ fn find_loop_size (key: u64) -> u64 {
   loops
 }
 
-fn parse_input() -> Result<(u64, u64), Box<dyn Error>> {
+fn parse_input(input: &InputSource) -> Result<(u64, u64), Error> {
   let mut result = vec![];
-  let lines = helpers::read_lines(INPUT_FILE)?.map(|l| l.unwrap());
-  for line in lines {
-    result.push(line.parse().unwrap())
+  let lines = helpers::read_lines(input)?;
+  for (i, line) in lines.enumerate() {
+    let line = line?;
+    result.push(SourceLine::new(DAY, i + 1, &line).parse::<u64>(&line)?);
+  }
+  match result.as_slice() {
+    &[card_key, door_key] => Ok((card_key, door_key)),
+    _ => Err(ParseError::missing_line(DAY, result.len() + 1, "expected exactly two public keys").into()),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn solves_example() {
+    let input = Day25::parse(&InputSource::File("input/test/day25".into())).unwrap();
+    assert_eq!(Day25::part_one(&input).unwrap(), Answer::Int(14897079));
+    assert_eq!(Day25::part_two(&input).unwrap(), Answer::None);
+  }
+
+  #[test]
+  fn reports_malformed_input() {
+    match Day25::parse(&InputSource::Text("5764801\n".into())) {
+      Err(Error::Parse(e)) => assert_eq!((e.day, e.line, e.column), (25, 2, 1)),
+      _ => panic!("expected a parse error"),
+    }
   }
-  Ok((result[0], result[1]))
 }
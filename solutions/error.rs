@@ -0,0 +1,132 @@
+use crate::day20::ImageError;
+use std::error;
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+
+#[derive(Debug)]
+pub enum Error {
+  Io(io::Error),
+  Parse(ParseError),
+  Usage(String),
+  Answers(String),
+  Image(ImageError),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ParseError {
+  pub day: u8,
+  pub line: usize,
+  pub column: usize,
+  pub text: String,
+  pub message: String,
+}
+
+// One line of puzzle input, used to point parse errors at the offending text
+pub struct SourceLine<'a> {
+  pub day: u8,
+  pub number: usize,
+  pub text: &'a str,
+}
+
+impl<'a> SourceLine<'a> {
+  pub fn new(day: u8, number: usize, text: &'a str) -> SourceLine<'a> {
+    SourceLine { day, number, text }
+  }
+
+  pub fn numbered(day: u8, lines: &'a [String]) -> impl Iterator<Item=SourceLine<'a>> {
+    lines.iter().enumerate().map(move |(i, line)| SourceLine::new(day, i + 1, line))
+  }
+
+  // `part` should be a slice of the line; anything else is reported at the end of it
+  pub fn error(&self, part: &str, message: &str) -> ParseError {
+    let start = self.text.as_ptr() as usize;
+    let offset = (part.as_ptr() as usize).wrapping_sub(start);
+    let offset = if offset <= self.text.len() { offset } else { self.text.len() };
+    ParseError {
+      day: self.day,
+      line: self.number,
+      column: self.text[..offset].chars().count() + 1,
+      text: self.text.to_string(),
+      message: message.to_string(),
+    }
+  }
+
+  pub fn parse<T: FromStr>(&self, part: &str) -> Result<T, ParseError> {
+    part.trim().parse::<T>().map_err(|_| self.error(part, &format!("invalid number '{}'", part.trim())))
+  }
+
+  pub fn expect<'b>(&self, part: Option<&'b str>, message: &str) -> Result<&'b str, ParseError> {
+    part.ok_or_else(|| self.error(&self.text[self.text.len()..], message))
+  }
+}
+
+impl ParseError {
+  // For input that ends before a required line
+  pub fn missing_line(day: u8, line: usize, message: &str) -> ParseError {
+    ParseError { day, line, column: 1, text: String::new(), message: message.to_string() }
+  }
+}
+
+impl fmt::Display for ParseError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    writeln!(f, "day {}, line {}, column {}: {}", self.day, self.line, self.column, self.message)?;
+    writeln!(f, "  {}", self.text)?;
+    write!(f, "  {}^", " ".repeat(self.column - 1))
+  }
+}
+
+impl fmt::Display for Error {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Error::Io(e) => write!(f, "{}", e),
+      Error::Parse(e) => write!(f, "{}", e),
+      Error::Usage(message) => write!(f, "{}", message),
+      Error::Answers(message) => write!(f, "{}", message),
+      Error::Image(e) => write!(f, "day 20: {}", e),
+    }
+  }
+}
+
+impl error::Error for ParseError {}
+
+impl error::Error for Error {}
+
+impl From<io::Error> for Error {
+  fn from(e: io::Error) -> Error {
+    Error::Io(e)
+  }
+}
+
+impl From<ParseError> for Error {
+  fn from(e: ParseError) -> Error {
+    Error::Parse(e)
+  }
+}
+
+impl From<ImageError> for Error {
+  fn from(e: ImageError) -> Error {
+    Error::Image(e)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn points_at_offending_part() {
+    let text = "acc +1x";
+    let line = SourceLine::new(8, 3, text);
+    let error = line.parse::<i32>(&text[4..]).unwrap_err();
+    assert_eq!((error.day, error.line, error.column), (8, 3, 5));
+    assert_eq!(error.text, "acc +1x");
+  }
+
+  #[test]
+  fn reports_missing_parts_at_end_of_line() {
+    let line = SourceLine::new(8, 1, "nop");
+    let error = line.expect(None, "expected an argument").unwrap_err();
+    assert_eq!(error.column, 4);
+  }
+}
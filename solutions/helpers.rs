@@ -1,9 +1,42 @@
 use std::fs::File;
 use std::io::{self, BufRead};
-use std::path::Path;
+use std::path::PathBuf;
 
-pub fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
-}
\ No newline at end of file
+pub mod automaton;
+pub mod bigint;
+pub mod grid;
+
+pub use grid::{Grid, GridCell};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Part {
+  One,
+  Two,
+  Both,
+}
+
+impl Part {
+  pub fn includes_one(&self) -> bool {
+    *self != Part::Two
+  }
+
+  pub fn includes_two(&self) -> bool {
+    *self != Part::One
+  }
+}
+
+#[derive(Clone, Debug)]
+pub enum InputSource {
+  File(PathBuf),
+  Stdin,
+  Text(String),
+}
+
+pub fn read_lines(input: &InputSource) -> io::Result<io::Lines<Box<dyn BufRead>>> {
+    let reader: Box<dyn BufRead> = match input {
+      InputSource::File(path) => Box::new(io::BufReader::new(File::open(path)?)),
+      InputSource::Stdin => Box::new(io::BufReader::new(io::stdin())),
+      InputSource::Text(text) => Box::new(io::Cursor::new(text.clone())),
+    };
+    Ok(reader.lines())
+}
@@ -0,0 +1,117 @@
+use std::collections::{HashMap, HashSet};
+
+// A cell's coordinates, one per dimension
+pub type Cell = Vec<i64>;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Neighbourhood {
+  // Every cell at most one step away along each axis, in the given number of dimensions
+  Moore(usize),
+  Offsets(Vec<Cell>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rules {
+  pub neighbourhood: Neighbourhood,
+  // Active neighbour counts that turn an inactive cell active
+  pub birth: Vec<usize>,
+  // Active neighbour counts that keep an active cell active
+  pub survival: Vec<usize>,
+}
+
+// An unbounded automaton that only stores its active cells
+#[derive(Clone, Debug)]
+pub struct Automaton {
+  offsets: Vec<Cell>,
+  birth: Vec<usize>,
+  survival: Vec<usize>,
+  active: HashSet<Cell>,
+}
+
+impl Neighbourhood {
+  pub fn offsets(&self) -> Vec<Cell> {
+    match self {
+      Neighbourhood::Moore(dimensions) => {
+        let mut offsets: Vec<Cell> = vec![vec![]];
+        for _ in 0..*dimensions {
+          offsets = offsets.into_iter()
+            .flat_map(|offset| (-1..=1).map(move |d| [offset.as_slice(), &[d]].concat()))
+            .collect();
+        }
+        offsets.into_iter().filter(|offset| offset.iter().any(|&d| d != 0)).collect()
+      },
+      Neighbourhood::Offsets(offsets) => offsets.clone(),
+    }
+  }
+}
+
+impl Automaton {
+  pub fn new(rules: &Rules, active: impl IntoIterator<Item=Cell>) -> Automaton {
+    Automaton {
+      offsets: rules.neighbourhood.offsets(),
+      birth: rules.birth.clone(),
+      survival: rules.survival.clone(),
+      active: active.into_iter().collect(),
+    }
+  }
+
+  pub fn active(&self) -> &HashSet<Cell> {
+    &self.active
+  }
+
+  pub fn step(&mut self) {
+    let mut counts: HashMap<Cell, usize> = HashMap::new();
+    for cell in &self.active {
+      for offset in &self.offsets {
+        let neighbour = cell.iter().zip(offset).map(|(c, d)| c + d).collect();
+        *counts.entry(neighbour).or_insert(0) += 1;
+      }
+    }
+
+    let mut next: HashSet<Cell> = HashSet::new();
+    if self.survival.contains(&0) {
+      next.extend(self.active.iter().filter(|cell| !counts.contains_key(*cell)).cloned());
+    }
+    for (cell, count) in counts {
+      let rule = if self.active.contains(&cell) { &self.survival } else { &self.birth };
+      if rule.contains(&count) {
+        next.insert(cell);
+      }
+    }
+
+    self.active = next;
+  }
+
+  pub fn run(&mut self, steps: usize) {
+    for _ in 0..steps {
+      self.step();
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn life() -> Rules {
+    Rules { neighbourhood: Neighbourhood::Moore(2), birth: vec![3], survival: vec![2, 3] }
+  }
+
+  #[test]
+  fn builds_moore_neighbourhoods() {
+    assert_eq!(Neighbourhood::Moore(1).offsets(), vec![vec![-1], vec![1]]);
+    assert_eq!(Neighbourhood::Moore(3).offsets().len(), 26);
+    assert_eq!(Neighbourhood::Moore(4).offsets().len(), 80);
+  }
+
+  #[test]
+  fn oscillates_blinker() {
+    let blinker = vec![vec![0, -1], vec![0, 0], vec![0, 1]];
+    let mut automaton = Automaton::new(&life(), blinker.clone());
+    automaton.step();
+    let expected: HashSet<Cell> = vec![vec![-1, 0], vec![0, 0], vec![1, 0]].into_iter().collect();
+    assert_eq!(automaton.active(), &expected);
+    automaton.step();
+    assert_eq!(automaton.active(), &blinker.into_iter().collect());
+  }
+}
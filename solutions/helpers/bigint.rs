@@ -0,0 +1,233 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+
+// Magnitudes are little-endian base 2^32 digits without trailing zeros, so zero has no digits
+type Digits = Vec<u32>;
+
+// A signed integer of any size, for results that don't fit in an i64
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BigInt {
+  negative: bool,
+  magnitude: Digits,
+}
+
+impl BigInt {
+  pub fn zero() -> BigInt {
+    BigInt { negative: false, magnitude: vec![] }
+  }
+
+  pub fn is_zero(&self) -> bool {
+    self.magnitude.is_empty()
+  }
+
+  fn from_parts(negative: bool, mut magnitude: Digits) -> BigInt {
+    trim(&mut magnitude);
+    let negative = negative && !magnitude.is_empty();
+    BigInt { negative, magnitude }
+  }
+
+  // Quotient and remainder truncated towards zero like the i64 operators, None when dividing by zero
+  pub fn div_rem(&self, rhs: &BigInt) -> Option<(BigInt, BigInt)> {
+    if rhs.is_zero() {
+      return None;
+    }
+    let (quotient, remainder) = div_rem_magnitude(&self.magnitude, &rhs.magnitude);
+    Some((
+      BigInt::from_parts(self.negative != rhs.negative, quotient),
+      BigInt::from_parts(self.negative, remainder),
+    ))
+  }
+}
+
+impl From<i64> for BigInt {
+  fn from(n: i64) -> BigInt {
+    let abs = n.unsigned_abs();
+    BigInt::from_parts(n < 0, vec![abs as u32, (abs >> 32) as u32])
+  }
+}
+
+impl Add for &BigInt {
+  type Output = BigInt;
+
+  fn add(self, rhs: &BigInt) -> BigInt {
+    if self.negative == rhs.negative {
+      return BigInt::from_parts(self.negative, add_magnitude(&self.magnitude, &rhs.magnitude));
+    }
+    match compare_magnitude(&self.magnitude, &rhs.magnitude) {
+      Ordering::Less => BigInt::from_parts(rhs.negative, sub_magnitude(&rhs.magnitude, &self.magnitude)),
+      _ => BigInt::from_parts(self.negative, sub_magnitude(&self.magnitude, &rhs.magnitude)),
+    }
+  }
+}
+
+impl Neg for &BigInt {
+  type Output = BigInt;
+
+  fn neg(self) -> BigInt {
+    BigInt::from_parts(!self.negative, self.magnitude.clone())
+  }
+}
+
+impl Sub for &BigInt {
+  type Output = BigInt;
+
+  fn sub(self, rhs: &BigInt) -> BigInt {
+    self + &-rhs
+  }
+}
+
+impl Mul for &BigInt {
+  type Output = BigInt;
+
+  fn mul(self, rhs: &BigInt) -> BigInt {
+    let mut product = vec![0u32; self.magnitude.len() + rhs.magnitude.len()];
+    for (i, &a) in self.magnitude.iter().enumerate() {
+      let mut carry = 0u64;
+      for (j, &b) in rhs.magnitude.iter().enumerate() {
+        let digit = product[i + j] as u64 + a as u64 * b as u64 + carry;
+        product[i + j] = digit as u32;
+        carry = digit >> 32;
+      }
+      product[i + rhs.magnitude.len()] = carry as u32;
+    }
+    BigInt::from_parts(self.negative != rhs.negative, product)
+  }
+}
+
+impl fmt::Display for BigInt {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    if self.is_zero() {
+      return write!(f, "0");
+    }
+
+    // Peel off nine decimal digits at a time, least significant first
+    let mut chunks = vec![];
+    let mut rest = self.magnitude.clone();
+    while !rest.is_empty() {
+      let (quotient, remainder) = div_rem_small(&rest, 1_000_000_000);
+      chunks.push(remainder);
+      rest = quotient;
+    }
+
+    if self.negative {
+      write!(f, "-")?;
+    }
+    write!(f, "{}", chunks[chunks.len() - 1])?;
+    for chunk in chunks.iter().rev().skip(1) {
+      write!(f, "{:09}", chunk)?;
+    }
+    Ok(())
+  }
+}
+
+fn trim(digits: &mut Digits) {
+  while digits.last() == Some(&0) {
+    digits.pop();
+  }
+}
+
+fn compare_magnitude(a: &[u32], b: &[u32]) -> Ordering {
+  a.len().cmp(&b.len()).then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_magnitude(a: &[u32], b: &[u32]) -> Digits {
+  let mut sum = vec![];
+  let mut carry = 0u64;
+  for i in 0..a.len().max(b.len()) {
+    let digit = *a.get(i).unwrap_or(&0) as u64 + *b.get(i).unwrap_or(&0) as u64 + carry;
+    sum.push(digit as u32);
+    carry = digit >> 32;
+  }
+  sum.push(carry as u32);
+  trim(&mut sum);
+  sum
+}
+
+// a - b, where a is at least b
+fn sub_magnitude(a: &[u32], b: &[u32]) -> Digits {
+  let mut difference = vec![];
+  let mut borrow = 0i64;
+  for (i, &a_digit) in a.iter().enumerate() {
+    let mut digit = a_digit as i64 - *b.get(i).unwrap_or(&0) as i64 - borrow;
+    borrow = if digit < 0 { 1 } else { 0 };
+    if digit < 0 {
+      digit += 1 << 32;
+    }
+    difference.push(digit as u32);
+  }
+  trim(&mut difference);
+  difference
+}
+
+fn div_rem_small(a: &[u32], divisor: u32) -> (Digits, u32) {
+  let mut quotient = vec![0u32; a.len()];
+  let mut remainder = 0u64;
+  for i in (0..a.len()).rev() {
+    let current = (remainder << 32) | a[i] as u64;
+    quotient[i] = (current / divisor as u64) as u32;
+    remainder = current % divisor as u64;
+  }
+  trim(&mut quotient);
+  (quotient, remainder as u32)
+}
+
+// Binary long division; the homework only needs it for the occasional huge divisor
+fn div_rem_magnitude(a: &[u32], b: &[u32]) -> (Digits, Digits) {
+  if b.len() == 1 {
+    let (quotient, remainder) = div_rem_small(a, b[0]);
+    return (quotient, vec![remainder]);
+  }
+
+  let mut quotient = vec![0u32; a.len()];
+  let mut remainder: Digits = vec![];
+  for bit in (0..a.len() * 32).rev() {
+    remainder = add_magnitude(&remainder, &remainder);
+    if a[bit / 32] >> (bit % 32) & 1 == 1 {
+      remainder = add_magnitude(&remainder, &[1]);
+    }
+    if compare_magnitude(&remainder, b) != Ordering::Less {
+      remainder = sub_magnitude(&remainder, b);
+      quotient[bit / 32] |= 1 << (bit % 32);
+    }
+  }
+  trim(&mut quotient);
+  (quotient, remainder)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn big(n: i64) -> BigInt {
+    BigInt::from(n)
+  }
+
+  #[test]
+  fn matches_i64_arithmetic() {
+    let values = [0, 1, -1, 7, -13, 4_294_967_296, -9_876_543_210, i32::MAX as i64 + 5];
+    for &a in &values {
+      for &b in &values {
+        assert_eq!(&big(a) + &big(b), big(a + b), "{} + {}", a, b);
+        assert_eq!(&big(a) - &big(b), big(a - b), "{} - {}", a, b);
+        if let Some(product) = a.checked_mul(b) {
+          assert_eq!(&big(a) * &big(b), big(product), "{} * {}", a, b);
+        }
+        if b != 0 {
+          assert_eq!(big(a).div_rem(&big(b)), Some((big(a / b), big(a % b))), "{} / {}", a, b);
+        }
+      }
+      assert_eq!(big(a).to_string(), a.to_string());
+    }
+    assert_eq!(big(5).div_rem(&BigInt::zero()), None);
+  }
+
+  #[test]
+  fn goes_beyond_u64() {
+    let max = big(i64::MAX);
+    let square = &max * &max;
+    assert_eq!(square.to_string(), "85070591730234615847396907784232501249");
+    assert_eq!(square.div_rem(&max), Some((max.clone(), BigInt::zero())));
+    assert_eq!((&-&square + &big(1)).to_string(), "-85070591730234615847396907784232501248");
+  }
+}
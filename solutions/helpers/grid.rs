@@ -0,0 +1,274 @@
+use crate::error::{ParseError, SourceLine};
+use std::fmt;
+use std::iter;
+use std::ops::{Index, IndexMut};
+
+// (x, y) with x counting columns from the left and y counting rows from the top
+pub type Point = (usize, usize);
+
+pub type Offset = (i64, i64);
+
+pub const DIRECTIONS_4: [Offset; 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+pub const DIRECTIONS_8: [Offset; 8] = [(0, -1), (1, -1), (1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1)];
+
+// Cells that are drawn as a single character in the puzzle input
+pub trait GridCell: Sized {
+  fn from_char(c: char) -> Option<Self>;
+  fn to_char(&self) -> char;
+}
+
+impl GridCell for bool {
+  fn from_char(c: char) -> Option<bool> {
+    match c {
+      '#' => Some(true),
+      '.' => Some(false),
+      _ => None,
+    }
+  }
+
+  fn to_char(&self) -> char {
+    if *self { '#' } else { '.' }
+  }
+}
+
+impl GridCell for char {
+  fn from_char(c: char) -> Option<char> {
+    Some(c)
+  }
+
+  fn to_char(&self) -> char {
+    *self
+  }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Grid<T> {
+  width: usize,
+  height: usize,
+  cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+  pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Grid<T>> {
+    let width = rows.first().map_or(0, |row| row.len());
+    if rows.iter().any(|row| row.len() != width) {
+      return None;
+    }
+    let height = rows.len();
+    Some(Grid { width, height, cells: rows.into_iter().flatten().collect() })
+  }
+
+  pub fn from_fn(width: usize, height: usize, f: impl Fn(Point) -> T) -> Grid<T> {
+    let cells = (0..height).flat_map(|y| (0..width).map(move |x| (x, y))).map(f).collect();
+    Grid { width, height, cells }
+  }
+
+  // Every line becomes a row; all rows must have the same length
+  pub fn parse_with<'a>(lines: impl IntoIterator<Item=SourceLine<'a>>, cell: impl Fn(char) -> Option<T>) -> Result<Grid<T>, ParseError> {
+    let mut cells = vec![];
+    let mut width = None;
+    let mut height = 0;
+
+    for line in lines {
+      let mut row_width = 0;
+      for (i, c) in line.text.char_indices() {
+        match cell(c) {
+          Some(value) => cells.push(value),
+          None => return Err(line.error(&line.text[i..], &format!("unexpected '{}'", c))),
+        }
+        row_width += 1;
+      }
+
+      match width {
+        None => width = Some(row_width),
+        Some(w) if w != row_width => {
+          let end = line.text.char_indices().nth(w).map_or(line.text.len(), |(i, _)| i);
+          return Err(line.error(&line.text[end..], &format!("expected a row of length {}", w)));
+        },
+        _ => (),
+      }
+      height += 1;
+    }
+
+    Ok(Grid { width: width.unwrap_or(0), height, cells })
+  }
+
+  pub fn width(&self) -> usize {
+    self.width
+  }
+
+  pub fn height(&self) -> usize {
+    self.height
+  }
+
+  pub fn get(&self, (x, y): Point) -> Option<&T> {
+    if x < self.width && y < self.height { Some(&self.cells[y * self.width + x]) } else { None }
+  }
+
+  pub fn get_mut(&mut self, (x, y): Point) -> Option<&mut T> {
+    if x < self.width && y < self.height { Some(&mut self.cells[y * self.width + x]) } else { None }
+  }
+
+  // Indexes as if the grid repeated forever in every direction
+  pub fn get_wrapping(&self, x: i64, y: i64) -> &T {
+    let x = x.rem_euclid(self.width as i64) as usize;
+    let y = y.rem_euclid(self.height as i64) as usize;
+    &self[(x, y)]
+  }
+
+  pub fn offset(&self, (x, y): Point, (dx, dy): Offset) -> Option<Point> {
+    let x = x as i64 + dx;
+    let y = y as i64 + dy;
+    if x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height {
+      Some((x as usize, y as usize))
+    } else {
+      None
+    }
+  }
+
+  pub fn points(&self) -> impl Iterator<Item=Point> {
+    let width = self.width;
+    (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item=&T> {
+    self.cells.iter()
+  }
+
+  pub fn rows(&self) -> impl Iterator<Item=&[T]> {
+    self.cells.chunks(self.width.max(1))
+  }
+
+  pub fn neighbours<'a>(&'a self, point: Point, directions: &'a [Offset]) -> impl Iterator<Item=Point> + 'a {
+    directions.iter().filter_map(move |&direction| self.offset(point, direction))
+  }
+
+  pub fn neighbours_4(&self, point: Point) -> impl Iterator<Item=Point> + '_ {
+    self.neighbours(point, &DIRECTIONS_4)
+  }
+
+  pub fn neighbours_8(&self, point: Point) -> impl Iterator<Item=Point> + '_ {
+    self.neighbours(point, &DIRECTIONS_8)
+  }
+
+  // The points seen when walking from `point` (not included) in `direction` until leaving the grid
+  pub fn ray(&self, point: Point, direction: Offset) -> impl Iterator<Item=Point> + '_ {
+    iter::successors(self.offset(point, direction), move |&p| self.offset(p, direction))
+  }
+
+  pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+    Grid { width: self.width, height: self.height, cells: self.cells.iter().map(f).collect() }
+  }
+}
+
+impl<T: Clone> Grid<T> {
+  pub fn filled(width: usize, height: usize, value: T) -> Grid<T> {
+    Grid { width, height, cells: vec![value; width * height] }
+  }
+
+  pub fn rotate_right(&self) -> Grid<T> {
+    Grid::from_fn(self.height, self.width, |(x, y)| self[(y, self.height - x - 1)].clone())
+  }
+
+  pub fn flip_horizontal(&self) -> Grid<T> {
+    Grid::from_fn(self.width, self.height, |(x, y)| self[(self.width - x - 1, y)].clone())
+  }
+
+  pub fn flip_vertical(&self) -> Grid<T> {
+    Grid::from_fn(self.width, self.height, |(x, y)| self[(x, self.height - y - 1)].clone())
+  }
+
+  pub fn transpose(&self) -> Grid<T> {
+    Grid::from_fn(self.height, self.width, |(x, y)| self[(y, x)].clone())
+  }
+
+  // The four rotations, each followed by its mirror image
+  pub fn orientations(&self) -> Vec<Grid<T>> {
+    let mut result = vec![];
+    let mut grid = self.clone();
+    for _ in 0..4 {
+      let flipped = grid.flip_horizontal();
+      let rotated = grid.rotate_right();
+      result.push(grid);
+      result.push(flipped);
+      grid = rotated;
+    }
+    result
+  }
+}
+
+impl<T: GridCell> Grid<T> {
+  pub fn parse<'a>(lines: impl IntoIterator<Item=SourceLine<'a>>) -> Result<Grid<T>, ParseError> {
+    Grid::parse_with(lines, T::from_char)
+  }
+}
+
+impl<T> Index<Point> for Grid<T> {
+  type Output = T;
+
+  fn index(&self, (x, y): Point) -> &T {
+    assert!(x < self.width && y < self.height, "({}, {}) is outside the {}x{} grid", x, y, self.width, self.height);
+    &self.cells[y * self.width + x]
+  }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+  fn index_mut(&mut self, (x, y): Point) -> &mut T {
+    assert!(x < self.width && y < self.height, "({}, {}) is outside the {}x{} grid", x, y, self.width, self.height);
+    &mut self.cells[y * self.width + x]
+  }
+}
+
+impl<T: GridCell> fmt::Display for Grid<T> {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    for (y, row) in self.rows().enumerate() {
+      if y > 0 {
+        writeln!(f)?;
+      }
+      for cell in row {
+        write!(f, "{}", cell.to_char())?;
+      }
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn grid(text: &str) -> Grid<char> {
+    let lines: Vec<String> = text.lines().map(String::from).collect();
+    Grid::parse(SourceLine::numbered(0, &lines)).unwrap()
+  }
+
+  #[test]
+  fn parses_and_displays() {
+    assert_eq!(grid("ab\ncd").to_string(), "ab\ncd");
+    let lines = vec!["#.".to_string(), "#".to_string()];
+    let error = Grid::<bool>::parse(SourceLine::numbered(0, &lines)).unwrap_err();
+    assert_eq!((error.line, error.column), (2, 2));
+  }
+
+  #[test]
+  fn transforms() {
+    let g = grid("ab\ncd\nef");
+    assert_eq!(g.rotate_right().to_string(), "eca\nfdb");
+    assert_eq!(g.flip_horizontal().to_string(), "ba\ndc\nfe");
+    assert_eq!(g.flip_vertical().to_string(), "ef\ncd\nab");
+    assert_eq!(g.transpose().to_string(), "ace\nbdf");
+    assert_eq!(g.rotate_right().rotate_right().rotate_right().rotate_right(), g);
+    assert_eq!(g.orientations().len(), 8);
+  }
+
+  #[test]
+  fn walks_neighbours_and_rays() {
+    let g = grid("abc\ndef\nghi");
+    assert_eq!(g.neighbours_4((0, 0)).map(|p| g[p]).collect::<String>(), "bd");
+    assert_eq!(g.neighbours_8((1, 1)).count(), 8);
+    assert_eq!(g.ray((0, 0), (1, 1)).map(|p| g[p]).collect::<String>(), "ei");
+    assert_eq!(*g.get_wrapping(-1, 4), 'f');
+    assert_eq!(g.get((3, 0)), None);
+  }
+}
@@ -0,0 +1,68 @@
+pub mod answers;
+pub mod error;
+pub mod helpers;
+pub mod solution;
+
+pub mod day01;
+pub mod day02;
+pub mod day03;
+pub mod day04;
+pub mod day05;
+pub mod day06;
+pub mod day07;
+pub mod day08;
+pub mod day09;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day20;
+pub mod day21;
+pub mod day22;
+pub mod day23;
+pub mod day24;
+pub mod day25;
+
+use error::Error;
+use helpers::{InputSource, Part};
+use solution::{Answers, Timings};
+
+pub type Solver = fn(&InputSource, Part) -> Result<(Answers, Timings), Error>;
+
+// Default input file and solver for a day
+pub type Day = (&'static str, Solver);
+
+pub const DAYS: [Day; 25] = [
+  (day01::INPUT_FILE, solution::solve::<day01::Day01>),
+  (day02::INPUT_FILE, solution::solve::<day02::Day02>),
+  (day03::INPUT_FILE, solution::solve::<day03::Day03>),
+  (day04::INPUT_FILE, solution::solve::<day04::Day04>),
+  (day05::INPUT_FILE, solution::solve::<day05::Day05>),
+  (day06::INPUT_FILE, solution::solve::<day06::Day06>),
+  (day07::INPUT_FILE, solution::solve::<day07::Day07>),
+  (day08::INPUT_FILE, solution::solve::<day08::Day08>),
+  (day09::INPUT_FILE, solution::solve::<day09::Day09>),
+  (day10::INPUT_FILE, solution::solve::<day10::Day10>),
+  (day11::INPUT_FILE, solution::solve::<day11::Day11>),
+  (day12::INPUT_FILE, solution::solve::<day12::Day12>),
+  (day13::INPUT_FILE, solution::solve::<day13::Day13>),
+  (day14::INPUT_FILE, solution::solve::<day14::Day14>),
+  (day15::INPUT_FILE, solution::solve::<day15::Day15>),
+  (day16::INPUT_FILE, solution::solve::<day16::Day16>),
+  (day17::INPUT_FILE, solution::solve::<day17::Day17>),
+  (day18::INPUT_FILE, solution::solve::<day18::Day18>),
+  (day19::INPUT_FILE, solution::solve::<day19::Day19>),
+  (day20::INPUT_FILE, solution::solve::<day20::Day20>),
+  (day21::INPUT_FILE, solution::solve::<day21::Day21>),
+  (day22::INPUT_FILE, solution::solve::<day22::Day22>),
+  (day23::INPUT_FILE, solution::solve::<day23::Day23>),
+  (day24::INPUT_FILE, solution::solve::<day24::Day24>),
+  (day25::INPUT_FILE, solution::solve::<day25::Day25>),
+];
+
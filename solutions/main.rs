@@ -1,10 +1,400 @@
-pub mod helpers;
+use advent_of_code_2020::answers;
+use advent_of_code_2020::day08::{self, Day08};
+use advent_of_code_2020::day08::trace::TraceFormat;
+use advent_of_code_2020::day18::{self, Day18};
+use advent_of_code_2020::day18::precedence::{PrecedenceTable, PRESETS};
+use advent_of_code_2020::day19::{self, Day19};
+use advent_of_code_2020::day19::{derivation, dfa};
+use advent_of_code_2020::day19::grammar::Grammar;
+use advent_of_code_2020::day20::{self, Day20};
+use advent_of_code_2020::day20::export::{self, ImageFormat};
+use advent_of_code_2020::day20::patterns::{self, Pattern};
+use advent_of_code_2020::error::Error;
+use advent_of_code_2020::helpers::bigint::BigInt;
+use advent_of_code_2020::helpers::{InputSource, Part};
+use advent_of_code_2020::solution::{Answer, Solution};
+use advent_of_code_2020::DAYS;
+use std::env;
+use std::fs::File;
+use std::io::{self, Read};
+use std::process;
+use std::time::Duration;
 
-mod day25;
+const USAGE: &str = "Usage: solutions <day> [part] [--input <path>|-] [--time]
+       solutions all [--time]
+       solutions verify [answers-file]
+       solutions bench <day>|all [part] [--input <path>|-] [--runs <n>]
+       solutions debug [--input <path>]
+       solutions repair [--input <path>|-]
+       solutions trace <output-file> [--input <path>|-] [--format jsonl|csv] [--patch <ip>|auto]
+       solutions precedence <preset>|<levels> [--input <path>|-] [--big]
+       solutions repl day18 [--parens]
+       solutions derive <message> [part] [--input <path>|-]
+       solutions regex [part] [--input <path>|-]
+       solutions image <output-file> [pattern-file...] [--input <path>|-] [--format pbm|pgm|ppm] [--keep-borders] [--border <n>]
+       solutions patterns [pattern-file...] [--input <path>|-] [--border <n>]";
+
+const DEFAULT_BENCH_RUNS: usize = 10;
+
+struct Options {
+  input: Option<InputSource>,
+  time: bool,
+  runs: usize,
+  format: Option<String>,
+  patch: Option<String>,
+  big: bool,
+  parens: bool,
+  keep_borders: bool,
+  border: usize,
+}
 
 fn main() {
-    match day25::solve() {
-        Ok(_) => println!("Done!"),
-        Err(e) => println!("Error: {}", e)
+  let args: Vec<String> = env::args().skip(1).collect();
+  match run(&args) {
+    Ok(_) => println!("Done!"),
+    Err(e) => {
+      println!("Error: {}", e);
+      process::exit(1);
+    }
+  }
+}
+
+fn run(args: &[String]) -> Result<(), Error> {
+  let (args, options) = split_options(args)?;
+  let args: Vec<&str> = args.iter().map(|a| a.as_str()).collect();
+
+  match args.as_slice() {
+    ["all"] if options.input.is_none() => {
+      let mut total = Duration::new(0, 0);
+      for day in 1..=DAYS.len() {
+        total += run_day(day, Part::Both, &options)?;
+      }
+      if options.time {
+        println!("Total: {}", format_duration(total));
+      }
+      Ok(())
+    },
+    ["verify"] if options.input.is_none() => verify_all(answers::ANSWERS_FILE),
+    ["verify", answers_file] if options.input.is_none() => verify_all(answers_file),
+    ["bench", "all"] if options.input.is_none() => {
+      for day in 1..=DAYS.len() {
+        bench_day(day, Part::Both, &options)?;
+      }
+      Ok(())
+    },
+    ["all"] | ["verify"] | ["verify", _] | ["bench", "all"] =>
+      Err(Error::Usage(format!("--input can't be combined with all, verify or bench all\n{}", USAGE))),
+    ["debug"] => debug_day08(&options),
+    ["repair"] => {
+      println!("{}", day08::repair::explain(&Day08::parse(&day_input(8, &options))?));
+      Ok(())
+    },
+    ["trace", output_file] => trace_day08(output_file, &options),
+    ["precedence", table] => sum_day18(table, &options),
+    ["derive", message] => derive_day19(message, Part::One, &options),
+    ["derive", message, part] => derive_day19(message, parse_part(part)?, &options),
+    ["regex"] => regex_day19(Part::One, &options),
+    ["regex", part] => regex_day19(parse_part(part)?, &options),
+    ["image", output_file, pattern_files @ ..] => image_day20(output_file, pattern_files, &options),
+    ["patterns", pattern_files @ ..] => patterns_day20(pattern_files, &options),
+    ["repl", day] => match parse_day(day)? {
+      18 => {
+        let stdin = io::stdin();
+        day18::repl::repl(stdin.lock(), io::stdout(), options.parens)?;
+        Ok(())
+      },
+      day => Err(Error::Usage(format!("There is no REPL for day {}, only day18\n{}", day, USAGE))),
+    },
+    ["bench", day] => bench_day(parse_day(day)?, Part::Both, &options),
+    ["bench", day, part] => bench_day(parse_day(day)?, parse_part(part)?, &options),
+    [day] => run_day(parse_day(day)?, Part::Both, &options).map(|_| ()),
+    [day, part] => run_day(parse_day(day)?, parse_part(part)?, &options).map(|_| ()),
+    _ => Err(Error::Usage(USAGE.to_string())),
+  }
+}
+
+fn day_input(day: usize, options: &Options) -> InputSource {
+  let (input_file, _) = DAYS[day - 1];
+  options.input.clone().unwrap_or_else(|| InputSource::File(input_file.into()))
+}
+
+fn run_day(day: usize, part: Part, options: &Options) -> Result<Duration, Error> {
+  let (_, solve) = DAYS[day - 1];
+  println!("Day {}", day);
+  let ((part_one, part_two), (parse_time, part_one_time, part_two_time)) = solve(&day_input(day, options), part)?;
+
+  if options.time {
+    println!("Parse: {}", format_duration(parse_time));
+  }
+  print_answer(1, part_one, part_one_time, options.time);
+  print_answer(2, part_two, part_two_time, options.time);
+
+  Ok(parse_time + part_one_time.unwrap_or_default() + part_two_time.unwrap_or_default())
+}
+
+fn print_answer(part: u8, answer: Option<Answer>, time: Option<Duration>, show_time: bool) {
+  match (answer, time) {
+    (Some(answer), Some(time)) if show_time => println!("Part {}: {} ({})", part, answer, format_duration(time)),
+    (Some(answer), _) => println!("Part {}: {}", part, answer),
+    (None, _) => (),
+  }
+}
+
+fn bench_day(day: usize, part: Part, options: &Options) -> Result<(), Error> {
+  let (_, solve) = DAYS[day - 1];
+  // Stdin can only be read once, so keep it in memory for the repeated runs
+  let input = match day_input(day, options) {
+    InputSource::Stdin => {
+      let mut text = String::new();
+      io::stdin().read_to_string(&mut text)?;
+      InputSource::Text(text)
+    },
+    input => input,
+  };
+
+  let mut parse_times = vec![];
+  let mut part_one_times = vec![];
+  let mut part_two_times = vec![];
+  for _ in 0..options.runs {
+    let (_, (parse_time, part_one_time, part_two_time)) = solve(&input, part)?;
+    parse_times.push(parse_time);
+    part_one_times.extend(part_one_time);
+    part_two_times.extend(part_two_time);
+  }
+
+  println!("Day {} ({} runs)", day, options.runs);
+  print_bench("Parse", &mut parse_times);
+  print_bench("Part 1", &mut part_one_times);
+  print_bench("Part 2", &mut part_two_times);
+  Ok(())
+}
+
+// Steps through the day 8 boot code, reading debugger commands from stdin
+fn debug_day08(options: &Options) -> Result<(), Error> {
+  let input = day_input(8, options);
+  if let InputSource::Stdin = input {
+    return Err(Error::Usage(format!("The debugger reads its commands from stdin, use --input <path>\n{}", USAGE)));
+  }
+
+  let program = Day08::parse(&input)?;
+  let stdin = io::stdin();
+  day08::debugger::repl(&program, stdin.lock(), io::stdout())?;
+  Ok(())
+}
+
+// Writes every instruction the day 8 boot code executes, optionally with one nop/jmp flipped
+fn trace_day08(output_file: &str, options: &Options) -> Result<(), Error> {
+  let format = match options.format.as_deref() {
+    Some(name) => TraceFormat::from_name(name)
+      .ok_or_else(|| Error::Usage(format!("Unknown trace format '{}', expected jsonl or csv\n{}", name, USAGE)))?,
+    None if output_file.ends_with(".csv") => TraceFormat::Csv,
+    None => TraceFormat::JsonLines,
+  };
+
+  let mut program = Day08::parse(&day_input(8, options))?;
+  if let Some(patch) = &options.patch {
+    let ip = match patch.as_str() {
+      "auto" => day08::repair::find_repair(&program).map(|(ip, _)| ip)
+        .ok_or_else(|| Error::Usage("No single nop/jmp flip makes the program terminate".to_string()))?,
+      ip => ip.parse::<usize>().map_err(|_| Error::Usage(format!("--patch needs an instruction index or auto\n{}", USAGE)))?,
+    };
+    program[ip] = program.get(ip).and_then(|instruction| instruction.flipped())
+      .ok_or_else(|| Error::Usage(format!("Instruction {} is not a nop or jmp", ip)))?;
+    println!("Flipped instruction {} to {}", ip, program[ip]);
+  }
+
+  let (terminated, acc) = day08::trace::write_trace(&program, format, File::create(output_file)?)?;
+  println!("{} with acc {}, trace written to {}", if terminated { "Terminated" } else { "Looped" }, acc, output_file);
+  Ok(())
+}
+
+// Sums the day 18 homework with a precedence table other than the two from the puzzle,
+// optionally with big integers instead of i64
+fn sum_day18(table: &str, options: &Options) -> Result<(), Error> {
+  let table = PrecedenceTable::parse(table)
+    .map_err(|e| Error::Usage(format!("{}, expected one of {} or levels like \"+ - < * / %\"\n{}", e, PRESETS.join(", "), USAGE)))?;
+
+  let lines = Day18::parse(&day_input(18, options))?;
+  let sum = if options.big {
+    day18::sum_lines::<BigInt>(&lines, &table)?.to_string()
+  } else {
+    day18::sum_lines::<i64>(&lines, &table)?.to_string()
+  };
+  println!("Sum with {}: {}", table, sum);
+  Ok(())
+}
+
+// Shows how a message matches rule 0 of day 19, or where it stops matching. Part two uses the
+// recursive rules 8 and 11.
+fn derive_day19(message: &str, part: Part, options: &Options) -> Result<(), Error> {
+  let (rules, _) = Day19::parse(&day_input(19, options))?;
+  let rules = if part == Part::Two { day19::part_two_rules(&rules)? } else { rules };
+
+  let grammar = Grammar::new(&rules);
+  let explanation = derivation::explain(&grammar, 0, message);
+  println!("{}", derivation::format_explanation(&grammar, message, &explanation));
+  Ok(())
+}
+
+// Prints rule 0 of day 19 as a regular expression, if it isn't recursive
+fn regex_day19(part: Part, options: &Options) -> Result<(), Error> {
+  let (rules, _) = Day19::parse(&day_input(19, options))?;
+  let rules = if part == Part::Two { day19::part_two_rules(&rules)? } else { rules };
+
+  match dfa::to_regex(&Grammar::new(&rules), 0) {
+    Some(regex) => println!("{}", regex),
+    None => println!("Rule 0 is recursive, so it has no regular expression"),
+  }
+  Ok(())
+}
+
+// The assembled day 20 tiles, and the patterns from the given files or else the sea monster
+fn day20_input(pattern_files: &[&str], options: &Options) -> Result<(day20::PartialImage, Vec<Pattern>), Error> {
+  let image = day20::assemble_image(&Day20::parse(&day_input(20, options))?)?;
+  let patterns = if pattern_files.is_empty() {
+    vec![day20::sea_monster()]
+  } else {
+    pattern_files.iter().map(|path| day20::parse_pattern(path)).collect::<Result<_, _>>()?
+  };
+  Ok((image, patterns))
+}
+
+// Writes the assembled day 20 picture as a plain netpbm image, with the cells the patterns
+// cover in red for ppm
+fn image_day20(output_file: &str, pattern_files: &[&str], options: &Options) -> Result<(), Error> {
+  let format = match options.format.as_deref() {
+    Some(name) => ImageFormat::from_name(name)
+      .ok_or_else(|| Error::Usage(format!("Unknown image format '{}', expected pbm, pgm or ppm\n{}", name, USAGE)))?,
+    None if output_file.ends_with(".pgm") => ImageFormat::Pgm,
+    None if output_file.ends_with(".ppm") => ImageFormat::Ppm,
+    None => ImageFormat::Pbm,
+  };
+
+  let (image, patterns) = day20_input(pattern_files, options)?;
+  let pixels = day20::render_image(&image, &patterns, options.border, options.keep_borders)?;
+  export::write_image(&pixels, format, File::create(output_file)?)?;
+  println!("{}x{} image written to {}", pixels.width(), pixels.height(), output_file);
+  Ok(())
+}
+
+// Lists every place the patterns appear in the assembled day 20 picture and the roughness left
+fn patterns_day20(pattern_files: &[&str], options: &Options) -> Result<(), Error> {
+  let (image, patterns) = day20_input(pattern_files, options)?;
+  let (matches, roughness) = day20::search_patterns(&image, &patterns, options.border)?;
+  for found in &matches {
+    println!("{}", patterns::format_match(&patterns, found));
+  }
+  println!("{} match(es), roughness {}", matches.len(), roughness);
+  Ok(())
+}
+
+fn print_bench(label: &str, times: &mut [Duration]) {
+  if times.is_empty() {
+    return;
+  }
+  times.sort();
+  println!("  {:<7} min {:>12}  median {:>12}  max {:>12}",
+    label,
+    format_duration(times[0]),
+    format_duration(times[times.len() / 2]),
+    format_duration(times[times.len() - 1]),
+  );
+}
+
+fn format_duration(duration: Duration) -> String {
+  format!("{:.3}ms", duration.as_secs_f64() * 1000.0)
+}
+
+fn verify_all(answers_file: &str) -> Result<(), Error> {
+  let expected = answers::read_answers(answers_file)?;
+
+  let mut mismatches = 0;
+  for (i, (input_file, solve)) in DAYS.iter().enumerate() {
+    let day = i + 1;
+    let ((part_one, part_two), _) = solve(&InputSource::File(input_file.into()), Part::Both)?;
+    let (expected_one, expected_two) = expected.get(&day).cloned().unwrap_or((None, None));
+    mismatches += verify_part(day, 1, part_one, expected_one);
+    mismatches += verify_part(day, 2, part_two, expected_two);
+  }
+
+  if mismatches > 0 {
+    Err(Error::Answers(format!("{} answer(s) differ from {}", mismatches, answers_file)))
+  } else {
+    Ok(())
+  }
+}
+
+// Answers are compared as printed, so 123 and "123" in the answers file are equal
+fn verify_part(day: usize, part: u8, actual: Option<Answer>, expected: Option<Answer>) -> usize {
+  match (actual, expected) {
+    (Some(actual), Some(expected)) if actual.to_string() == expected.to_string() => {
+      println!("Day {} part {}: ok", day, part);
+      0
+    },
+    (Some(actual), Some(expected)) => {
+      println!("Day {} part {}: expected {}, got {}", day, part, expected, actual);
+      1
+    },
+    (Some(Answer::None), None) | (None, _) => 0,
+    (Some(actual), None) => {
+      println!("Day {} part {}: {} (no stored answer)", day, part, actual);
+      0
+    },
+  }
+}
+
+fn split_options(args: &[String]) -> Result<(Vec<String>, Options), Error> {
+  let mut rest = vec![];
+  let mut options = Options { input: None, time: false, runs: DEFAULT_BENCH_RUNS, format: None, patch: None, big: false, parens: false, keep_borders: false, border: day20::BORDER };
+  let mut iter = args.iter();
+  while let Some(arg) = iter.next() {
+    match arg.as_str() {
+      "--input" => {
+        options.input = match iter.next().map(|s| s.as_str()) {
+          Some("-") => Some(InputSource::Stdin),
+          Some(path) => Some(InputSource::File(path.into())),
+          None => return Err(Error::Usage(format!("--input needs a path or -\n{}", USAGE))),
+        };
+      },
+      "--time" => options.time = true,
+      "--big" => options.big = true,
+      "--parens" => options.parens = true,
+      "--keep-borders" => options.keep_borders = true,
+      "--runs" => {
+        options.runs = match iter.next().map(|s| s.parse::<usize>()) {
+          Some(Ok(runs)) if runs > 0 => runs,
+          _ => return Err(Error::Usage(format!("--runs needs a positive number\n{}", USAGE))),
+        };
+      },
+      "--border" => {
+        options.border = match iter.next().map(|s| s.parse::<usize>()) {
+          Some(Ok(border)) => border,
+          _ => return Err(Error::Usage(format!("--border needs a number of cells\n{}", USAGE))),
+        };
+      },
+      "--format" => {
+        options.format = Some(iter.next().cloned().ok_or_else(|| Error::Usage(format!("--format needs a format name\n{}", USAGE)))?);
+      },
+      "--patch" => {
+        options.patch = Some(iter.next().cloned().ok_or_else(|| Error::Usage(format!("--patch needs an instruction index or auto\n{}", USAGE)))?);
+      },
+      _ => rest.push(arg.clone()),
     }
+  }
+  Ok((rest, options))
+}
+
+fn parse_day(arg: &str) -> Result<usize, Error> {
+  match arg.trim_start_matches("day").parse::<usize>() {
+    Ok(day) if day >= 1 && day <= DAYS.len() => Ok(day),
+    _ => Err(Error::Usage(format!("Unknown day '{}'\n{}", arg, USAGE))),
+  }
+}
+
+fn parse_part(arg: &str) -> Result<Part, Error> {
+  match arg {
+    "1" => Ok(Part::One),
+    "2" => Ok(Part::Two),
+    _ => Err(Error::Usage(format!("Unknown part '{}', expected 1 or 2\n{}", arg, USAGE))),
+  }
 }
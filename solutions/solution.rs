@@ -0,0 +1,102 @@
+use crate::error::Error;
+use crate::helpers::{InputSource, Part};
+use std::convert::TryFrom;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Answer {
+  Int(i64),
+  Str(String),
+  None,
+}
+
+pub trait Solution {
+  type Input;
+
+  fn parse(input: &InputSource) -> Result<Self::Input, Error>;
+  // A part fails when the input parsed but can't be solved, like a sum that overflows
+  fn part_one(input: &Self::Input) -> Result<Answer, Error>;
+  fn part_two(input: &Self::Input) -> Result<Answer, Error>;
+}
+
+pub type Answers = (Option<Answer>, Option<Answer>);
+
+// Wall time spent parsing and in each part that was run
+pub type Timings = (Duration, Option<Duration>, Option<Duration>);
+
+pub fn solve<S: Solution>(input: &InputSource, part: Part) -> Result<(Answers, Timings), Error> {
+  let start = Instant::now();
+  let parsed = S::parse(input)?;
+  let parse_time = start.elapsed();
+
+  let (part_one, part_one_time) = if part.includes_one() { timed(|| S::part_one(&parsed))? } else { (None, None) };
+  let (part_two, part_two_time) = if part.includes_two() { timed(|| S::part_two(&parsed))? } else { (None, None) };
+
+  Ok(((part_one, part_two), (parse_time, part_one_time, part_two_time)))
+}
+
+fn timed(f: impl FnOnce() -> Result<Answer, Error>) -> Result<(Option<Answer>, Option<Duration>), Error> {
+  let start = Instant::now();
+  let answer = f()?;
+  Ok((Some(answer), Some(start.elapsed())))
+}
+
+impl fmt::Display for Answer {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Answer::Int(n) => write!(f, "{}", n),
+      Answer::Str(s) => write!(f, "{}", s),
+      Answer::None => write!(f, "-"),
+    }
+  }
+}
+
+impl From<i32> for Answer {
+  fn from(n: i32) -> Answer {
+    Answer::Int(n as i64)
+  }
+}
+
+impl From<u32> for Answer {
+  fn from(n: u32) -> Answer {
+    Answer::Int(n as i64)
+  }
+}
+
+impl From<i64> for Answer {
+  fn from(n: i64) -> Answer {
+    Answer::Int(n)
+  }
+}
+
+// Values that don't fit in an i64 are kept exact as strings
+impl From<u64> for Answer {
+  fn from(n: u64) -> Answer {
+    i64::try_from(n).map(Answer::Int).unwrap_or_else(|_| Answer::Str(n.to_string()))
+  }
+}
+
+impl From<u128> for Answer {
+  fn from(n: u128) -> Answer {
+    i64::try_from(n).map(Answer::Int).unwrap_or_else(|_| Answer::Str(n.to_string()))
+  }
+}
+
+impl From<usize> for Answer {
+  fn from(n: usize) -> Answer {
+    Answer::from(n as u64)
+  }
+}
+
+impl From<String> for Answer {
+  fn from(s: String) -> Answer {
+    Answer::Str(s)
+  }
+}
+
+impl<T: Into<Answer>> From<Option<T>> for Answer {
+  fn from(answer: Option<T>) -> Answer {
+    answer.map_or(Answer::None, Into::into)
+  }
+}